@@ -0,0 +1,28 @@
+#version 450
+
+layout(location = 0) in vec2 in_texture_coords;
+layout(location = 1) in flat uint in_frame;
+layout(location = 2) in vec4 in_tint;
+
+layout(set = 1, binding = 0) uniform texture2D atlas_texture;
+layout(set = 1, binding = 1) uniform sampler atlas_sampler;
+layout(set = 1, binding = 2) uniform AtlasInfo {
+    ivec2 size;
+} atlas_info;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    uint columns = (atlas_info.size.x >> 5);
+    uint frame_x = in_frame % columns;
+    uint frame_y = in_frame / columns;
+    vec2 frame_coords = vec2(in_texture_coords.x + frame_x, in_texture_coords.y + frame_y) * vec2(32, 32);
+    frame_coords /= atlas_info.size;
+
+    vec4 color = texture(sampler2D(atlas_texture, atlas_sampler), frame_coords).rgba;
+    if (color.a == 0) {
+        discard;
+    }
+
+    out_color = color * in_tint;
+}
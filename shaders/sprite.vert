@@ -0,0 +1,35 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform WorldData {
+    mat4 u_view;
+    mat4 u_projection;
+};
+
+layout(location = 0) in vec2 in_position;
+layout(location = 1) in vec4 in_tint;
+layout(location = 2) in uint in_frame;
+layout(location = 3) in uint in_flip;
+
+layout(location = 0) out vec2 out_texture_coords;
+layout(location = 1) out flat uint out_frame;
+layout(location = 2) out vec4 out_tint;
+
+const uint FLIP_X = 0x1u;
+const uint FLIP_Y = 0x2u;
+
+void main() {
+    vec2 corner = vec2(gl_VertexIndex & 0x1, gl_VertexIndex >> 1);
+
+    vec2 texture_coords = corner;
+    if ((in_flip & FLIP_X) != 0u) {
+        texture_coords.x = 1.0 - texture_coords.x;
+    }
+    if ((in_flip & FLIP_Y) != 0u) {
+        texture_coords.y = 1.0 - texture_coords.y;
+    }
+
+    gl_Position = u_projection * u_view * vec4(in_position + corner - vec2(0.5, 0.5), 0.0, 1.0);
+    out_texture_coords = texture_coords;
+    out_frame = in_frame;
+    out_tint = in_tint;
+}
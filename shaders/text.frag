@@ -0,0 +1,18 @@
+#version 450
+
+layout(location = 0) in vec2 in_texture_coords;
+layout(location = 1) in vec4 in_color;
+
+layout(set = 1, binding = 0) uniform texture2D font_texture;
+layout(set = 1, binding = 1) uniform sampler font_sampler;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    float alpha = texture(sampler2D(font_texture, font_sampler), in_texture_coords).a;
+    if (alpha == 0) {
+        discard;
+    }
+
+    out_color = vec4(in_color.rgb, in_color.a * alpha);
+}
@@ -0,0 +1,24 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform ScreenData {
+    vec2 u_screen_size;
+};
+
+layout(location = 0) in vec2 in_position;
+layout(location = 1) in vec4 in_color;
+layout(location = 2) in uint in_glyph;
+
+layout(location = 0) out vec2 out_texture_coords;
+layout(location = 1) out vec4 out_color;
+
+const vec2 GLYPH_SIZE = vec2(8.0, 16.0);
+const vec2 FONT_SIZE = vec2(128.0, 96.0);
+
+void main() {
+    vec2 corner = vec2(gl_VertexIndex & 0x1, gl_VertexIndex >> 1);
+    vec2 position = (in_position + corner * GLYPH_SIZE) / u_screen_size;
+
+    gl_Position = vec4(position.x * 2.0 - 1.0, 1.0 - position.y * 2.0, 0.0, 1.0);
+    out_texture_coords = (vec2(in_glyph & 0xfu, in_glyph >> 4u) + corner) * GLYPH_SIZE / FONT_SIZE;
+    out_color = in_color;
+}
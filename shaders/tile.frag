@@ -9,14 +9,46 @@ layout(set = 1, binding = 2) uniform TileSetInfo {
     ivec2 size;
 } tileset_info;
 
+// Tile count, then an offset for every tile which is zero for static ones. Each animation at its offset
+// is the total duration, the frame count and the frames as pairs of a tile index and a duration in milliseconds
+layout(set = 3, binding = 0) readonly buffer TileAnimations {
+    uint tile_animations[];
+};
+layout(set = 3, binding = 1) uniform TimeData {
+    uint u_time;
+};
+
 layout(location = 0) out vec4 out_color;
 
+uint animated_tile(uint tile_index) {
+    if (tile_index >= tile_animations[0]) {
+        return tile_index;
+    }
+
+    uint offset = tile_animations[1u + tile_index];
+    if (offset == 0u) {
+        return tile_index;
+    }
+
+    uint time = u_time % tile_animations[offset];
+    uint frame_count = tile_animations[offset + 1u];
+    for (uint i = 0u; i < frame_count; ++i) {
+        uint frame_duration = tile_animations[offset + 3u + 2u * i];
+        if (time < frame_duration) {
+            return tile_animations[offset + 2u + 2u * i];
+        }
+        time -= frame_duration;
+    }
+
+    return tile_index;
+}
+
 void main() {
     if (in_tile_index == 0) {
         discard;
     }
 
-    uint tile_index = in_tile_index - 1u;
+    uint tile_index = animated_tile(in_tile_index - 1u);
 
     uint columns = (tileset_info.size.x >> 5);
     uint tile_x = tile_index % columns;
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::resources;
+
+/// Looping sequence of tileset frames
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    frames: Vec<AnimationFrame>,
+    duration: f32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnimationFrame {
+    /// Zero based index of the frame image in the tileset
+    pub frame: u32,
+    /// Duration in seconds
+    pub duration: f32,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<AnimationFrame>) -> Self {
+        let duration = frames.iter().map(|frame| frame.duration).sum();
+        Self { frames, duration }
+    }
+
+    #[inline]
+    pub fn frames(&self) -> &[AnimationFrame] {
+        &self.frames
+    }
+
+    /// Frame shown `time` seconds after the animation started
+    pub fn frame_at(&self, time: f32) -> Option<u32> {
+        if self.duration <= 0.0 {
+            return self.frames.first().map(|frame| frame.frame);
+        }
+
+        let mut time = time % self.duration;
+        for frame in &self.frames {
+            if time < frame.duration {
+                return Some(frame.frame);
+            }
+            time -= frame.duration;
+        }
+        self.frames.last().map(|frame| frame.frame)
+    }
+}
+
+/// Tile animations of the tileset.
+///
+/// Animated tiles with `character` and `clip` string properties are also available as named clips,
+/// e.g. `walk_down` of the `player` character
+#[derive(Debug, Default)]
+pub struct Animations {
+    tiles: HashMap<u32, Animation>,
+    clips: HashMap<String, HashMap<String, u32>>,
+}
+
+impl Animations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads animations from a Tiled tileset
+    pub fn load(path: &PathBuf) -> Result<Self> {
+        let tileset = resources::load_json::<Tileset>(path)?;
+
+        let mut animations = Self::new();
+        for tile in tileset.tiles {
+            if tile.animation.is_empty() {
+                continue;
+            }
+
+            let frames = tile
+                .animation
+                .iter()
+                .map(|frame| AnimationFrame {
+                    frame: frame.tile_id,
+                    duration: frame.duration as f32 / 1000.0,
+                })
+                .collect();
+            animations.insert(tile.id, Animation::new(frames));
+
+            let property = |name: &str| {
+                tile.properties
+                    .iter()
+                    .find(|property| property.name == name)
+                    .and_then(|property| property.value.as_str())
+            };
+            if let (Some(character), Some(clip)) = (property(CHARACTER_PROPERTY), property(CLIP_PROPERTY)) {
+                animations.insert_clip(character, clip, tile.id);
+            }
+        }
+
+        Ok(animations)
+    }
+
+    pub fn insert(&mut self, tile: u32, animation: Animation) {
+        self.tiles.insert(tile, animation);
+    }
+
+    /// Names the animation of `tile` as a clip of `character`
+    pub fn insert_clip(&mut self, character: &str, clip: &str, tile: u32) {
+        self.clips
+            .entry(character.to_owned())
+            .or_default()
+            .insert(clip.to_owned(), tile);
+    }
+
+    #[inline]
+    pub fn get(&self, tile: u32) -> Option<&Animation> {
+        self.tiles.get(&tile)
+    }
+
+    /// Animated tiles with their animations
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Animation)> {
+        self.tiles.iter().map(|(tile, animation)| (*tile, animation))
+    }
+
+    /// Tile with the animation of the clip
+    pub fn clip(&self, character: &str, clip: &str) -> Option<u32> {
+        self.clips.get(character)?.get(clip).copied()
+    }
+}
+
+/// Frames of animated tiles in the layout read by `tile.frag`
+#[derive(Default)]
+pub struct TileAnimationTable {
+    /// Offset of the animation of every tile in `animations`
+    offsets: Vec<Option<u32>>,
+    animations: Vec<u32>,
+}
+
+impl TileAnimationTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_animations(animations: &Animations) -> Self {
+        let mut table = Self::new();
+        for (tile, animation) in animations.iter() {
+            let frames = animation
+                .frames()
+                .iter()
+                .map(|frame| (frame.frame, frame.duration))
+                .collect::<Vec<_>>();
+            table.insert(tile, &frames);
+        }
+        table
+    }
+
+    /// Animates a zero based `tile` with frames of tile indices and their durations in seconds
+    pub fn insert(&mut self, tile: u32, frames: &[(u32, f32)]) {
+        let frames = frames
+            .iter()
+            .map(|(frame, duration)| (*frame, (duration * 1000.0) as u32))
+            .collect::<Vec<_>>();
+
+        // Frames can't be resolved without a duration
+        let duration = frames.iter().map(|(_, duration)| duration).sum::<u32>();
+        if duration == 0 {
+            return;
+        }
+
+        if self.offsets.len() <= tile as usize {
+            self.offsets.resize(tile as usize + 1, None);
+        }
+        self.offsets[tile as usize] = Some(self.animations.len() as u32);
+
+        self.animations.push(duration);
+        self.animations.push(frames.len() as u32);
+        for (frame, duration) in frames {
+            self.animations.push(frame);
+            self.animations.push(duration);
+        }
+    }
+
+    /// Tile count, then the offset of every tile animation or zero, then the animations as
+    /// total duration, frame count and pairs of frames and their durations in milliseconds
+    pub fn to_data(&self) -> Vec<u32> {
+        let header = 1 + self.offsets.len() as u32;
+
+        let mut data = Vec::with_capacity(header as usize + self.animations.len());
+        data.push(self.offsets.len() as u32);
+        data.extend(self.offsets.iter().map(|offset| match offset {
+            Some(offset) => offset + header,
+            None => 0,
+        }));
+        data.extend_from_slice(&self.animations);
+        data
+    }
+}
+
+#[derive(Deserialize)]
+struct Tileset {
+    #[serde(default)]
+    tiles: Vec<Tile>,
+}
+
+#[derive(Deserialize)]
+struct Tile {
+    id: u32,
+    #[serde(default)]
+    animation: Vec<Frame>,
+    #[serde(default)]
+    properties: Vec<Property>,
+}
+
+#[derive(Deserialize)]
+struct Frame {
+    #[serde(rename = "tileid")]
+    tile_id: u32,
+    /// Duration in milliseconds
+    duration: u32,
+}
+
+#[derive(Deserialize)]
+struct Property {
+    name: String,
+    value: serde_json::Value,
+}
+
+const CHARACTER_PROPERTY: &str = "character";
+const CLIP_PROPERTY: &str = "clip";
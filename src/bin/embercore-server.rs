@@ -0,0 +1,18 @@
+use std::path::Path;
+
+use embercore_client_lib::config::Config;
+use embercore_client_lib::server::StandInServer;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+
+    let config = Config::new("configs/client.local.yml")?;
+
+    let server = StandInServer::bind(config.server_address, Path::new("content"), config.timeout()).await?;
+    log::info!("Listening on {}", server.local_address()?);
+
+    server.run().await?;
+
+    Ok(())
+}
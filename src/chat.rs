@@ -0,0 +1,272 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local, Utc};
+use winit::dpi::PhysicalSize;
+use winit::event::VirtualKeyCode;
+
+use crate::input::InputState;
+use crate::network::MAX_CHAT_MESSAGE_LENGTH;
+use crate::rendering::{TextBatch, GLYPH_SIZE};
+
+pub struct ChatEntry {
+    pub time: DateTime<Utc>,
+    pub from: String,
+    pub text: String,
+}
+
+/// Chat history and the text box.
+///
+/// Enter focuses the text box and sends its contents, Escape cancels, Page Up/Down scroll the history
+pub struct Chat {
+    history: VecDeque<ChatEntry>,
+    input: String,
+    focused: bool,
+    scroll: usize,
+}
+
+impl Chat {
+    pub fn new() -> Self {
+        Self {
+            history: VecDeque::new(),
+            input: String::new(),
+            focused: false,
+            scroll: 0,
+        }
+    }
+
+    #[inline]
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn push(&mut self, entry: ChatEntry) {
+        self.history.push_back(entry);
+        if self.history.len() > MAX_HISTORY_SIZE {
+            self.history.pop_front();
+        }
+
+        // Keep the viewed lines in place while scrolled up
+        if self.scroll > 0 {
+            self.scroll = (self.scroll + 1).min(self.max_scroll());
+        }
+    }
+
+    /// Handles typed text and chat keys. Returns text to send when the input is submitted
+    pub fn update(&mut self, input_state: &InputState) -> Option<String> {
+        let keyboard = input_state.keyboard();
+
+        if keyboard.was_pressed(VirtualKeyCode::PageUp) {
+            self.scroll = (self.scroll + SCROLL_STEP).min(self.max_scroll());
+        } else if keyboard.was_pressed(VirtualKeyCode::PageDown) {
+            self.scroll = self.scroll.saturating_sub(SCROLL_STEP);
+        }
+
+        if !self.focused {
+            if keyboard.was_pressed(VirtualKeyCode::Return) {
+                self.focused = true;
+            }
+            return None;
+        }
+
+        if keyboard.was_pressed(VirtualKeyCode::Escape) {
+            self.input.clear();
+            self.focused = false;
+            return None;
+        }
+
+        for c in input_state.text().chars() {
+            match c {
+                '\u{8}' => {
+                    self.input.pop();
+                }
+                c if c.is_control() => {}
+                c if self.input.chars().count() < MAX_CHAT_MESSAGE_LENGTH => self.input.push(c),
+                _ => {}
+            }
+        }
+
+        if keyboard.was_pressed(VirtualKeyCode::Return) {
+            self.focused = false;
+            self.scroll = 0;
+
+            let text = std::mem::take(&mut self.input);
+            if !text.trim().is_empty() {
+                return Some(text);
+            }
+        }
+
+        None
+    }
+
+    /// Lays out the visible history and the text box in the bottom left corner of the screen
+    pub fn layout(&self, batch: &mut TextBatch, screen_size: PhysicalSize<u32>) {
+        let line_count = VISIBLE_LINES + 1;
+        let top = screen_size.height as f32 - (line_count as f32 + 1.0) * GLYPH_SIZE[1];
+
+        if self.focused {
+            batch.push_rect([MARGIN, top], [BOX_WIDTH, line_count as u32], BACKGROUND_COLOR);
+        }
+
+        let end = self.history.len() - self.scroll;
+        let start = end.saturating_sub(VISIBLE_LINES);
+        let offset = VISIBLE_LINES - (end - start);
+
+        for (i, entry) in self.history.iter().skip(start).take(end - start).enumerate() {
+            let line = format!(
+                "[{}] {}: {}",
+                entry.time.with_timezone(&Local).format("%H:%M:%S"),
+                entry.from,
+                entry.text
+            );
+            let position = [MARGIN, top + (offset + i) as f32 * GLYPH_SIZE[1]];
+            batch.push_text(position, TEXT_COLOR, &line);
+        }
+
+        if self.focused {
+            let position = [MARGIN, top + VISIBLE_LINES as f32 * GLYPH_SIZE[1]];
+            batch.push_text(position, INPUT_COLOR, &format!("> {}_", self.input));
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.history.len().saturating_sub(VISIBLE_LINES)
+    }
+}
+
+const MAX_HISTORY_SIZE: usize = 200;
+const VISIBLE_LINES: usize = 8;
+const SCROLL_STEP: usize = 4;
+
+const MARGIN: f32 = 8.0;
+const BOX_WIDTH: u32 = 80;
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const INPUT_COLOR: [f32; 4] = [1.0, 0.9, 0.5, 1.0];
+const BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+
+#[cfg(test)]
+mod tests {
+    use winit::event::{DeviceId, ElementState, KeyboardInput, ModifiersState, WindowEvent};
+
+    use super::*;
+
+    /// Runs a frame in which `keys` are pressed and `text` is typed
+    fn update(chat: &mut Chat, keys: &[VirtualKeyCode], text: &str) -> Option<String> {
+        let mut input = InputState::new();
+        for key in keys {
+            #[allow(deprecated)]
+            input.handle_window_event(&WindowEvent::KeyboardInput {
+                device_id: unsafe { DeviceId::dummy() },
+                input: KeyboardInput {
+                    scancode: 0,
+                    state: ElementState::Pressed,
+                    virtual_keycode: Some(*key),
+                    modifiers: ModifiersState::empty(),
+                },
+                is_synthetic: false,
+            });
+        }
+        for c in text.chars() {
+            input.handle_window_event(&WindowEvent::ReceivedCharacter(c));
+        }
+        chat.update(&input)
+    }
+
+    fn push(chat: &mut Chat, count: usize) {
+        for i in 0..count {
+            chat.push(ChatEntry {
+                time: Utc::now(),
+                from: "server".to_owned(),
+                text: i.to_string(),
+            });
+        }
+    }
+
+    #[test]
+    fn input_is_edited_and_submitted() {
+        let mut chat = Chat::new();
+
+        // Text is ignored until the input is focused
+        assert_eq!(update(&mut chat, &[], "ignored"), None);
+        assert_eq!(update(&mut chat, &[VirtualKeyCode::Return], ""), None);
+        assert!(chat.is_focused());
+        assert!(chat.input.is_empty());
+
+        // Backspace removes characters, other control characters are skipped
+        assert_eq!(update(&mut chat, &[], "help\u{8}\u{8}llo\t"), None);
+        assert_eq!(chat.input, "hello");
+
+        assert_eq!(
+            update(&mut chat, &[VirtualKeyCode::Return], "!"),
+            Some("hello!".to_owned())
+        );
+        assert!(!chat.is_focused());
+        assert!(chat.input.is_empty());
+
+        // Blank messages are not sent
+        update(&mut chat, &[VirtualKeyCode::Return], "");
+        assert_eq!(update(&mut chat, &[VirtualKeyCode::Return], "  "), None);
+        assert!(!chat.is_focused());
+
+        // Escape drops the input
+        update(&mut chat, &[VirtualKeyCode::Return], "");
+        update(&mut chat, &[], "draft");
+        assert_eq!(update(&mut chat, &[VirtualKeyCode::Escape], ""), None);
+        assert!(!chat.is_focused());
+        assert!(chat.input.is_empty());
+    }
+
+    #[test]
+    fn input_length_is_limited() {
+        let mut chat = Chat::new();
+        update(&mut chat, &[VirtualKeyCode::Return], "");
+
+        // Counted in characters rather than bytes
+        let text = "ä".repeat(MAX_CHAT_MESSAGE_LENGTH + 10);
+        update(&mut chat, &[], &text);
+        assert_eq!(chat.input.chars().count(), MAX_CHAT_MESSAGE_LENGTH);
+
+        // Space freed by backspace can be used again
+        update(&mut chat, &[], "\u{8}b");
+        assert_eq!(chat.input.chars().count(), MAX_CHAT_MESSAGE_LENGTH);
+        assert!(chat.input.ends_with('b'));
+    }
+
+    #[test]
+    fn scrollback_is_bounded() {
+        let mut chat = Chat::new();
+        push(&mut chat, VISIBLE_LINES + 12);
+
+        update(&mut chat, &[VirtualKeyCode::PageUp], "");
+        assert_eq!(chat.scroll, SCROLL_STEP);
+        for _ in 0..5 {
+            update(&mut chat, &[VirtualKeyCode::PageUp], "");
+        }
+        assert_eq!(chat.scroll, 12);
+
+        // New messages keep the viewed lines in place
+        push(&mut chat, 1);
+        assert_eq!(chat.scroll, 13);
+
+        for _ in 0..5 {
+            update(&mut chat, &[VirtualKeyCode::PageDown], "");
+        }
+        assert_eq!(chat.scroll, 0);
+        push(&mut chat, 1);
+        assert_eq!(chat.scroll, 0);
+
+        // Old messages are dropped, the scroll stays within the history
+        push(&mut chat, MAX_HISTORY_SIZE);
+        assert_eq!(chat.history.len(), MAX_HISTORY_SIZE);
+        for _ in 0..MAX_HISTORY_SIZE {
+            update(&mut chat, &[VirtualKeyCode::PageUp], "");
+        }
+        assert_eq!(chat.scroll, MAX_HISTORY_SIZE - VISIBLE_LINES);
+        push(&mut chat, 1);
+        assert_eq!(chat.scroll, MAX_HISTORY_SIZE - VISIBLE_LINES);
+
+        // Sending a message scrolls back down
+        update(&mut chat, &[VirtualKeyCode::Return], "");
+        update(&mut chat, &[VirtualKeyCode::Return], "hi");
+        assert_eq!(chat.scroll, 0);
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use crate::network::{ChunkData, ServerMessage};
+use crate::CHUNK_SIZE;
+
+/// Map chunks streamed by the server.
+///
+/// Chunks are keyed by layer, row and column, so they are iterated layer by layer from the top of the map
+pub struct LoadedChunks<T> {
+    chunks: BTreeMap<(u32, i32, i32), T>,
+}
+
+impl<T> LoadedChunks<T> {
+    pub fn new() -> Self {
+        Self {
+            chunks: BTreeMap::new(),
+        }
+    }
+
+    /// Loads or unloads the chunk of a `ChunkLoaded` or `ChunkUnloaded` message, other messages are ignored.
+    ///
+    /// `load` creates the data of a new chunk, replacing the chunk at the same place
+    pub fn apply<F>(&mut self, message: &ServerMessage, load: F)
+    where
+        F: FnOnce(&ChunkData) -> T,
+    {
+        match message {
+            ServerMessage::ChunkLoaded(chunk) => {
+                if chunk.tiles.len() != CHUNK_SIZE * CHUNK_SIZE {
+                    log::warn!("Invalid chunk size: {}", chunk.tiles.len());
+                    return;
+                }
+                self.chunks.insert((chunk.layer, chunk.y, chunk.x), load(chunk));
+            }
+            ServerMessage::ChunkUnloaded { layer, x, y } => {
+                self.chunks.remove(&(*layer, *y, *x));
+            }
+            _ => {}
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, layer: u32, x: i32, y: i32) -> bool {
+        self.chunks.contains_key(&(layer, y, x))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Layers with at least one loaded chunk
+    pub fn layers(&self) -> Vec<u32> {
+        let mut layers = self.chunks.keys().map(|(layer, _, _)| *layer).collect::<Vec<_>>();
+        layers.dedup();
+        layers
+    }
+
+    /// Chunks with their layer, row and column
+    pub fn iter(&self) -> impl Iterator<Item = (&(u32, i32, i32), &T)> {
+        self.chunks.iter()
+    }
+}
+
+impl<T> Default for LoadedChunks<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+/// Blocked tiles of a map. Everything outside of the map is blocked too
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollisionGrid {
+    width: u32,
+    height: u32,
+    /// One bit per tile, row by row
+    blocked: Vec<u8>,
+}
+
+impl CollisionGrid {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            blocked: vec![0; (width as usize * height as usize + 7) / 8],
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn set_blocked(&mut self, x: u32, y: u32, blocked: bool) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let index = y as usize * self.width as usize + x as usize;
+        if blocked {
+            self.blocked[index / 8] |= 1 << (index % 8);
+        } else {
+            self.blocked[index / 8] &= !(1 << (index % 8));
+        }
+    }
+
+    pub fn is_blocked(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return true;
+        }
+
+        let index = y as usize * self.width as usize + x as usize;
+        self.blocked[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    /// Moves a box centered at `position` by `delta`, stopping at blocked tiles.
+    ///
+    /// Axes are resolved separately, so the box slides along walls instead of sticking to them.
+    /// Tiles in between are checked too, so fast movement can't tunnel through thin walls
+    pub fn move_and_slide(&self, position: glm::Vec2, half_size: glm::Vec2, delta: glm::Vec2) -> glm::Vec2 {
+        let x = self.sweep(position, half_size, delta.x, 0);
+        let y = self.sweep(glm::vec2(x, position.y), half_size, delta.y, 1);
+        glm::vec2(x, y)
+    }
+
+    fn sweep(&self, position: glm::Vec2, half_size: glm::Vec2, delta: f32, axis: usize) -> f32 {
+        if delta == 0.0 {
+            return position[axis];
+        }
+
+        // Tiles only touched by an edge don't count as overlapped
+        let other = 1 - axis;
+        let first_row = (position[other] - half_size[other] + EPSILON).floor() as i32;
+        let last_row = (position[other] + half_size[other] - EPSILON).ceil() as i32 - 1;
+
+        let is_blocked = |line: i32| {
+            (first_row..=last_row).any(|row| match axis {
+                0 => self.is_blocked(line, row),
+                _ => self.is_blocked(row, line),
+            })
+        };
+
+        if delta > 0.0 {
+            let edge = position[axis] + half_size[axis];
+            let first_line = (edge - EPSILON).ceil() as i32;
+            let last_line = (edge + delta).ceil() as i32 - 1;
+
+            match (first_line..=last_line).find(|line| is_blocked(*line)) {
+                Some(line) => (line as f32 - half_size[axis]).max(position[axis]),
+                None => position[axis] + delta,
+            }
+        } else {
+            let edge = position[axis] - half_size[axis];
+            let first_line = (edge + EPSILON).floor() as i32 - 1;
+            let last_line = (edge + delta).floor() as i32;
+
+            match (last_line..=first_line).rev().find(|line| is_blocked(*line)) {
+                Some(line) => ((line + 1) as f32 + half_size[axis]).min(position[axis]),
+                None => position[axis] + delta,
+            }
+        }
+    }
+}
+
+/// Tolerance for boxes resting exactly against a tile edge
+const EPSILON: f32 = 1e-4;
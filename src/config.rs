@@ -1,11 +1,37 @@
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use config::{Config as RowConfig, ConfigError, File, FileFormat};
 use serde::Deserialize;
 
+use crate::network::{Credentials, TransportKind};
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub server_address: SocketAddr,
+    #[serde(default)]
+    pub transport: TransportKind,
+    pub credentials: Credentials,
+    #[serde(default = "default_interpolation_delay_ms")]
+    pub interpolation_delay_ms: u64,
+    #[serde(default = "default_max_extrapolation_ms")]
+    pub max_extrapolation_ms: u64,
+    /// Time without any message from the server after which the connection is considered lost
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate: u32,
+    /// How fast the camera catches up with the player, `0` disables smoothing
+    #[serde(default = "default_camera_smoothing")]
+    pub camera_smoothing: f32,
+    /// Distance in tiles the player can move from the screen center before the camera follows
+    #[serde(default = "default_camera_deadzone")]
+    pub camera_deadzone: f32,
+    #[serde(default)]
+    pub record_file: Option<PathBuf>,
+    #[serde(default)]
+    pub replay_file: Option<PathBuf>,
 }
 
 impl Config {
@@ -14,4 +40,49 @@ impl Config {
         config.merge(File::new(path, FileFormat::Yaml))?;
         config.try_into()
     }
+
+    #[inline]
+    pub fn interpolation_delay(&self) -> Duration {
+        Duration::from_millis(self.interpolation_delay_ms)
+    }
+
+    #[inline]
+    pub fn max_extrapolation(&self) -> Duration {
+        Duration::from_millis(self.max_extrapolation_ms)
+    }
+
+    #[inline]
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    /// Duration of a single simulation tick
+    #[inline]
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_secs(1) / self.tick_rate.max(1)
+    }
+}
+
+fn default_interpolation_delay_ms() -> u64 {
+    100
+}
+
+fn default_max_extrapolation_ms() -> u64 {
+    250
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_tick_rate() -> u32 {
+    60
+}
+
+fn default_camera_smoothing() -> f32 {
+    8.0
+}
+
+fn default_camera_deadzone() -> f32 {
+    1.0
 }
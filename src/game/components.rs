@@ -0,0 +1,195 @@
+use std::collections::{HashMap, VecDeque};
+
+use specs::prelude::*;
+
+use crate::network::{EntityId, PropertyValue};
+
+/// Position in tiles
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Position(pub glm::Vec2);
+
+impl Component for Position {
+    type Storage = VecStorage<Self>;
+}
+
+/// Position at the start of the current tick
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PreviousPosition(pub glm::Vec2);
+
+impl Component for PreviousPosition {
+    type Storage = VecStorage<Self>;
+}
+
+/// Position between the last two ticks at the current frame
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RenderPosition(pub glm::Vec2);
+
+impl Component for RenderPosition {
+    type Storage = VecStorage<Self>;
+}
+
+/// Velocity in tiles per second
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Velocity(pub glm::Vec2);
+
+impl Component for Velocity {
+    type Storage = VecStorage<Self>;
+}
+
+/// Box centered at the position which can't enter blocked tiles
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Collider {
+    /// Half of the box size in tiles
+    pub half_size: glm::Vec2,
+}
+
+impl Component for Collider {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sprite {
+    /// Zero based index of the sprite image in the atlas
+    pub frame: u32,
+    /// Color multiplied with the image
+    pub tint: [f32; 4],
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl Sprite {
+    pub fn new(frame: u32) -> Self {
+        Self {
+            frame,
+            tint: [1.0; 4],
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+}
+
+impl Component for Sprite {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Plays a tileset animation on the sprite of the entity
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Animator {
+    /// Tile which has the animation in the tileset
+    animation: Option<u32>,
+    /// Seconds since the animation started
+    time: f32,
+    paused: bool,
+}
+
+impl Animator {
+    pub fn new(animation: Option<u32>) -> Self {
+        Self {
+            animation,
+            time: 0.0,
+            paused: false,
+        }
+    }
+
+    #[inline]
+    pub fn animation(&self) -> Option<u32> {
+        self.animation
+    }
+
+    #[inline]
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Switches to another animation, it keeps playing if it is the current one
+    pub fn play(&mut self, animation: u32) {
+        if self.animation != Some(animation) {
+            self.animation = Some(animation);
+            self.time = 0.0;
+        }
+        self.paused = false;
+    }
+
+    /// Rewinds to the first frame and stays there until the next `play`
+    pub fn stop(&mut self) {
+        self.time = 0.0;
+        self.paused = true;
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        if !self.paused {
+            self.time += dt;
+        }
+    }
+}
+
+impl Component for Animator {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Entity animated with the named clips of a character in the tileset.
+///
+/// Standing characters stay at the first frame of their animation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Character {
+    pub name: String,
+}
+
+impl Component for Character {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Marks the entity driven by the local player
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PlayerControlled;
+
+impl Component for PlayerControlled {
+    type Storage = NullStorage<Self>;
+}
+
+/// Tile centers the entity walks through, removed when the last one is reached
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub waypoints: VecDeque<glm::Vec2>,
+}
+
+impl Component for Path {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Entity replicated from the server
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Remote(pub EntityId);
+
+impl Component for Remote {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Entity spawned from a map object, removed when another map is loaded
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MapEntity;
+
+impl Component for MapEntity {
+    type Storage = NullStorage<Self>;
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Npc;
+
+impl Component for Npc {
+    type Storage = NullStorage<Self>;
+}
+
+/// Area with custom properties set in Tiled
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    /// Object name, shown as the sender of its messages
+    pub name: String,
+    /// Half of the area size in tiles
+    pub half_size: glm::Vec2,
+    pub properties: HashMap<String, PropertyValue>,
+}
+
+impl Component for Trigger {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,161 @@
+mod components;
+mod objects;
+mod resources;
+mod sprites;
+mod systems;
+mod timestep;
+
+pub use self::components::*;
+pub use self::objects::*;
+pub use self::resources::*;
+pub use self::sprites::*;
+pub use self::systems::*;
+pub use self::timestep::*;
+
+use specs::prelude::*;
+
+use crate::config::Config;
+use crate::network::{MapObject, PLAYER_HALF_SIZE};
+
+/// ECS world with the simulation running at a fixed tick rate and the systems which run every frame
+pub struct Game {
+    world: World,
+    tick_dispatcher: Dispatcher<'static, 'static>,
+    frame_dispatcher: Dispatcher<'static, 'static>,
+    objects: ObjectRegistry,
+    timestep: FixedTimestep,
+}
+
+impl Game {
+    pub fn new(config: &Config) -> Self {
+        let mut world = World::new();
+
+        let mut tick_dispatcher = DispatcherBuilder::new()
+            .with(PreviousPositionSystem, "previous_position", &[])
+            .with(PlayerInputSystem, "player_input", &[])
+            .with(InterpolationSystem, "interpolation", &[])
+            .with(RemoteEntitySystem, "remote_entities", &["interpolation"])
+            .with(PlayerSyncSystem, "player_sync", &["previous_position", "player_input"])
+            .with(
+                MovementSystem,
+                "movement",
+                &["previous_position", "player_sync", "remote_entities"],
+            )
+            .with(CharacterAnimationSystem, "character_animation", &["movement"])
+            .with(AnimationSystem, "animation", &["character_animation"])
+            .with(TriggerSystem::default(), "trigger", &["movement"])
+            .build();
+        tick_dispatcher.setup(&mut world);
+
+        let mut frame_dispatcher = DispatcherBuilder::new()
+            .with(UiSystem, "ui", &[])
+            .with(ClickToMoveSystem, "click_to_move", &[])
+            .with(RenderPositionSystem, "render_position", &[])
+            .with(
+                CameraSystem::new(config.camera_smoothing, config.camera_deadzone),
+                "camera",
+                &["render_position"],
+            )
+            .with_barrier()
+            .with(InputFlushSystem, "input_flush", &[])
+            .build();
+        frame_dispatcher.setup(&mut world);
+
+        // Components which no system reads, so the dispatchers do not register them
+        world.register::<MapEntity>();
+        world.register::<Npc>();
+
+        Self {
+            world,
+            tick_dispatcher,
+            frame_dispatcher,
+            objects: ObjectRegistry::with_builtin_types(),
+            timestep: FixedTimestep::new(config.tick_duration().as_secs_f32()),
+        }
+    }
+
+    /// Runs as many ticks as fit into the elapsed time, then the frame systems
+    pub fn update(&mut self, frame_time: f32) {
+        for _ in 0..self.timestep.advance(frame_time) {
+            self.world.insert(DeltaTime(self.timestep.tick_duration()));
+            self.tick_dispatcher.dispatch(&self.world);
+            self.world.maintain();
+        }
+
+        self.world.insert(FrameTime(frame_time));
+        self.world.insert(TickAlpha(self.timestep.alpha()));
+        self.frame_dispatcher.dispatch(&self.world);
+        self.world.maintain();
+    }
+
+    pub fn spawn_player(&mut self, position: glm::Vec2) -> Entity {
+        self.world
+            .create_entity()
+            .with(Position(position))
+            .with(PreviousPosition(position))
+            .with(Velocity(glm::vec2(0.0, 0.0)))
+            .with(Collider {
+                half_size: glm::vec2(PLAYER_HALF_SIZE, PLAYER_HALF_SIZE),
+            })
+            .with(Sprite::new(PLAYER_SPRITE))
+            .with(Animator::new(None))
+            .with(Character {
+                name: PLAYER_CHARACTER.to_owned(),
+            })
+            .with(PlayerControlled)
+            .build()
+    }
+
+    /// Moves the local player to a position set by the server, spawning it the first time
+    pub fn place_player(&mut self, position: glm::Vec2) {
+        let player = (&self.world.entities(), &self.world.read_storage::<PlayerControlled>())
+            .join()
+            .map(|(entity, _)| entity)
+            .next();
+
+        let player = match player {
+            Some(player) => player,
+            None => {
+                self.spawn_player(position);
+                return;
+            }
+        };
+
+        // Teleports without interpolating from the previous position
+        let _ = self
+            .world
+            .write_storage::<Position>()
+            .insert(player, Position(position));
+        let _ = self
+            .world
+            .write_storage::<PreviousPosition>()
+            .insert(player, PreviousPosition(position));
+        self.world.write_storage::<Path>().remove(player);
+    }
+
+    /// Replaces entities of the previous map with the objects of the new one
+    pub fn load_objects(&mut self, objects: &[MapObject]) {
+        let previous = (&self.world.entities(), &self.world.read_storage::<MapEntity>())
+            .join()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        if let Err(e) = self.world.delete_entities(&previous) {
+            log::error!("Failed to delete map entities: {}", e);
+        }
+
+        for object in objects {
+            self.objects.spawn(&mut self.world, object);
+        }
+        self.world.maintain();
+    }
+
+    #[inline]
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    #[inline]
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use specs::prelude::*;
+
+use crate::game::components::*;
+use crate::game::sprites::DEFAULT_NPC_SPRITE;
+use crate::network::{MapObject, PropertyValue, PLAYER_HALF_SIZE};
+
+/// Adds the components of a Tiled object type to a new entity
+pub type ObjectSpawner = Box<dyn for<'a> Fn(&MapObject, EntityBuilder<'a>) -> EntityBuilder<'a> + Send + Sync>;
+
+/// Object types which can be placed on maps in Tiled.
+///
+/// Every spawned entity has a `Position` at the object center and a `MapEntity` marker,
+/// the spawner registered for the object type adds everything else
+#[derive(Default)]
+pub struct ObjectRegistry {
+    spawners: HashMap<String, ObjectSpawner>,
+}
+
+impl ObjectRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with NPCs and triggers
+    pub fn with_builtin_types() -> Self {
+        let mut registry = Self::new();
+
+        registry.register("npc", |object, builder| {
+            let frame = match object.properties.get(SPRITE_PROPERTY) {
+                Some(PropertyValue::Int(frame)) => *frame as u32,
+                _ => DEFAULT_NPC_SPRITE,
+            };
+
+            let builder = builder
+                .with(Npc)
+                .with(Sprite::new(frame))
+                .with(Animator::new(Some(frame)))
+                .with(Collider {
+                    half_size: glm::vec2(PLAYER_HALF_SIZE, PLAYER_HALF_SIZE),
+                });
+
+            match object.properties.get(CHARACTER_PROPERTY) {
+                Some(PropertyValue::String(name)) => builder.with(Character { name: name.clone() }),
+                _ => builder,
+            }
+        });
+        registry.register("trigger", |object, builder| {
+            builder.with(Trigger {
+                name: object.name.clone(),
+                half_size: glm::vec2(object.size[0], object.size[1]) / 2.0,
+                properties: object.properties.clone(),
+            })
+        });
+
+        registry
+    }
+
+    /// Replaces the spawner of `kind` if there is one
+    pub fn register<F>(&mut self, kind: &str, spawner: F)
+    where
+        F: for<'a> Fn(&MapObject, EntityBuilder<'a>) -> EntityBuilder<'a> + Send + Sync + 'static,
+    {
+        self.spawners.insert(kind.to_owned(), Box::new(spawner));
+    }
+
+    /// Objects of unknown types are skipped
+    pub fn spawn(&self, world: &mut World, object: &MapObject) -> Option<Entity> {
+        let spawner = match self.spawners.get(&object.kind) {
+            Some(spawner) => spawner,
+            None => {
+                log::warn!("Unknown type {:?} of map object {}", object.kind, object.id);
+                return None;
+            }
+        };
+
+        let position = glm::vec2(object.position[0], object.position[1]);
+        let builder = world
+            .create_entity()
+            .with(Position(position))
+            .with(PreviousPosition(position))
+            .with(MapEntity);
+
+        Some(spawner(object, builder).build())
+    }
+}
+
+const SPRITE_PROPERTY: &str = "sprite";
+/// Name of the clips used when the NPC walks
+const CHARACTER_PROPERTY: &str = "character";
@@ -0,0 +1,39 @@
+use crate::network::ClientMessage;
+
+/// Duration of a simulation tick in seconds
+#[derive(Debug, Default, Copy, Clone)]
+pub struct DeltaTime(pub f32);
+
+/// Duration of the current frame in seconds
+#[derive(Debug, Default, Copy, Clone)]
+pub struct FrameTime(pub f32);
+
+/// Progress from the last simulation tick to the next one in `[0, 1)`, used to interpolate rendering
+#[derive(Debug, Default, Copy, Clone)]
+pub struct TickAlpha(pub f32);
+
+/// Messages produced by systems, sent to the server after the dispatch
+#[derive(Debug, Default)]
+pub struct Outbox {
+    messages: Vec<ClientMessage>,
+}
+
+impl Outbox {
+    #[inline]
+    pub fn push(&mut self, message: ClientMessage) {
+        self.messages.push(message);
+    }
+
+    #[inline]
+    pub fn drain(&mut self) -> impl Iterator<Item = ClientMessage> + '_ {
+        self.messages.drain(..)
+    }
+}
+
+/// Set when the player asks to close the game
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ExitRequested(pub bool);
+
+/// Size of the current map in tiles, unknown until the server sends it
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MapSize(pub Option<glm::Vec2>);
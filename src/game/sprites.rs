@@ -0,0 +1,8 @@
+// TODO: sprites should come from the server
+
+/// Tileset frame of players, the local one and the others
+pub const PLAYER_SPRITE: u32 = 1064;
+/// Clips of players in the tileset
+pub const PLAYER_CHARACTER: &str = "player";
+/// Tileset frame of NPCs without a `sprite` property
+pub const DEFAULT_NPC_SPRITE: u32 = 1029;
@@ -0,0 +1,80 @@
+use specs::prelude::*;
+
+use crate::animation::Animations;
+use crate::game::components::{Animator, Character, Position, PreviousPosition, Sprite};
+use crate::game::resources::DeltaTime;
+
+/// Picks walking clips of characters from the direction they moved during the tick
+pub struct CharacterAnimationSystem;
+
+impl<'a> System<'a> for CharacterAnimationSystem {
+    type SystemData = (
+        Read<'a, Animations>,
+        ReadStorage<'a, Character>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, PreviousPosition>,
+        WriteStorage<'a, Animator>,
+    );
+
+    fn run(&mut self, (animations, characters, positions, previous_positions, mut animators): Self::SystemData) {
+        for (character, position, previous, animator) in
+            (&characters, &positions, &previous_positions, &mut animators).join()
+        {
+            let delta = position.0 - previous.0;
+            if glm::length(&delta) < MIN_WALK_DISTANCE {
+                animator.stop();
+                continue;
+            }
+
+            let clip = if delta.x.abs() > delta.y.abs() {
+                if delta.x > 0.0 {
+                    WALK_RIGHT_CLIP
+                } else {
+                    WALK_LEFT_CLIP
+                }
+            } else if delta.y > 0.0 {
+                WALK_DOWN_CLIP
+            } else {
+                WALK_UP_CLIP
+            };
+
+            if let Some(animation) = animations.clip(&character.name, clip) {
+                animator.play(animation);
+            }
+        }
+    }
+}
+
+/// Advances animations on the simulation clock and shows their current frames
+pub struct AnimationSystem;
+
+impl<'a> System<'a> for AnimationSystem {
+    type SystemData = (
+        Read<'a, DeltaTime>,
+        Read<'a, Animations>,
+        WriteStorage<'a, Animator>,
+        WriteStorage<'a, Sprite>,
+    );
+
+    fn run(&mut self, (dt, animations, mut animators, mut sprites): Self::SystemData) {
+        for (animator, sprite) in (&mut animators, &mut sprites).join() {
+            animator.advance(dt.0);
+
+            let frame = animator
+                .animation()
+                .and_then(|animation| animations.get(animation))
+                .and_then(|animation| animation.frame_at(animator.time()));
+            if let Some(frame) = frame {
+                sprite.frame = frame;
+            }
+        }
+    }
+}
+
+/// Distance in tiles per tick below which a character is standing still
+const MIN_WALK_DISTANCE: f32 = 1e-4;
+
+const WALK_UP_CLIP: &str = "walk_up";
+const WALK_DOWN_CLIP: &str = "walk_down";
+const WALK_LEFT_CLIP: &str = "walk_left";
+const WALK_RIGHT_CLIP: &str = "walk_right";
@@ -0,0 +1,168 @@
+use specs::prelude::*;
+
+use crate::game::components::{PlayerControlled, RenderPosition};
+use crate::game::resources::{FrameTime, MapSize};
+use crate::rendering::Camera;
+
+/// Follows the local player with a deadzone and smoothing, keeping the view inside the map
+pub struct CameraSystem {
+    smoothing: f32,
+    deadzone: f32,
+}
+
+impl CameraSystem {
+    pub fn new(smoothing: f32, deadzone: f32) -> Self {
+        Self { smoothing, deadzone }
+    }
+}
+
+impl<'a> System<'a> for CameraSystem {
+    type SystemData = (
+        Read<'a, FrameTime>,
+        Read<'a, MapSize>,
+        WriteExpect<'a, Camera>,
+        ReadStorage<'a, PlayerControlled>,
+        ReadStorage<'a, RenderPosition>,
+    );
+
+    fn run(&mut self, (frame_time, map_size, mut camera, players, positions): Self::SystemData) {
+        let target = match (&players, &positions).join().next() {
+            Some((_, position)) => position.0,
+            None => return,
+        };
+
+        let current = *camera.position();
+        let offset = target - current;
+        let desired = current
+            + glm::vec2(
+                outside_deadzone(offset.x, self.deadzone),
+                outside_deadzone(offset.y, self.deadzone),
+            );
+
+        // Exponential smoothing which doesn't depend on the frame rate
+        let factor = if self.smoothing > 0.0 {
+            1.0 - (-self.smoothing * frame_time.0).exp()
+        } else {
+            1.0
+        };
+        let mut position = glm::lerp(&current, &desired, factor);
+
+        if let Some(map_size) = map_size.0 {
+            let half_extent = camera.half_extent();
+            position = glm::vec2(
+                clamp_to_map(position.x, half_extent.x, map_size.x),
+                clamp_to_map(position.y, half_extent.y, map_size.y),
+            );
+        }
+
+        camera.look_at(&position);
+    }
+}
+
+fn outside_deadzone(offset: f32, deadzone: f32) -> f32 {
+    if offset > deadzone {
+        offset - deadzone
+    } else if offset < -deadzone {
+        offset + deadzone
+    } else {
+        0.0
+    }
+}
+
+/// Centers maps smaller than the screen
+fn clamp_to_map(position: f32, half_extent: f32, map_size: f32) -> f32 {
+    if map_size <= half_extent * 2.0 {
+        map_size / 2.0
+    } else {
+        position.max(half_extent).min(map_size - half_extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::dpi::PhysicalSize;
+
+    use super::*;
+
+    /// World with an 800x600 camera at the origin, which shows 12.5x9.375 tiles
+    fn world(map_size: Option<glm::Vec2>) -> World {
+        let mut world = World::new();
+        world.register::<PlayerControlled>();
+        world.register::<RenderPosition>();
+        world.insert(FrameTime(0.1));
+        world.insert(MapSize(map_size));
+        world.insert(Camera::new(PhysicalSize::new(800, 600)));
+        world
+    }
+
+    fn follow(world: &mut World, system: &mut CameraSystem, target: glm::Vec2) -> glm::Vec2 {
+        world.delete_all();
+        world
+            .create_entity()
+            .with(PlayerControlled)
+            .with(RenderPosition(target))
+            .build();
+        world.maintain();
+
+        system.run_now(world);
+        *world.read_resource::<Camera>().position()
+    }
+
+    #[test]
+    fn camera_stays_until_the_player_leaves_the_deadzone() {
+        let mut world = world(None);
+        let mut system = CameraSystem::new(0.0, 1.0);
+
+        assert_eq!(
+            follow(&mut world, &mut system, glm::vec2(0.5, -0.8)),
+            glm::vec2(0.0, 0.0)
+        );
+        assert_eq!(
+            follow(&mut world, &mut system, glm::vec2(3.0, -2.0)),
+            glm::vec2(2.0, -1.0)
+        );
+        assert_eq!(
+            follow(&mut world, &mut system, glm::vec2(2.5, -1.5)),
+            glm::vec2(2.0, -1.0)
+        );
+    }
+
+    #[test]
+    fn camera_is_smoothed_by_frame_time() {
+        let mut world = world(None);
+        // Covers half of the remaining distance every frame
+        let mut system = CameraSystem::new(std::f32::consts::LN_2 / 0.1, 0.0);
+
+        let position = follow(&mut world, &mut system, glm::vec2(4.0, 2.0));
+        assert!(glm::distance(&position, &glm::vec2(2.0, 1.0)) < 1e-5, "{:?}", position);
+
+        let position = follow(&mut world, &mut system, glm::vec2(4.0, 2.0));
+        assert!(glm::distance(&position, &glm::vec2(3.0, 1.5)) < 1e-5, "{:?}", position);
+    }
+
+    #[test]
+    fn camera_is_clamped_to_the_map() {
+        let mut world = world(Some(glm::vec2(64.0, 64.0)));
+        let mut system = CameraSystem::new(0.0, 0.0);
+
+        assert_eq!(
+            follow(&mut world, &mut system, glm::vec2(1.0, 1.0)),
+            glm::vec2(6.25, 4.6875)
+        );
+        assert_eq!(
+            follow(&mut world, &mut system, glm::vec2(30.0, 20.0)),
+            glm::vec2(30.0, 20.0)
+        );
+        assert_eq!(
+            follow(&mut world, &mut system, glm::vec2(63.0, 63.0)),
+            glm::vec2(57.75, 59.3125)
+        );
+
+        // Maps smaller than the screen are centered
+        world.insert(MapSize(Some(glm::vec2(8.0, 8.0))));
+        assert_eq!(
+            follow(&mut world, &mut system, glm::vec2(1.0, 7.0)),
+            glm::vec2(4.0, 4.0)
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use specs::prelude::*;
+
+use crate::input::InputState;
+
+/// Clears per-frame input, must run after every system which reads it
+pub struct InputFlushSystem;
+
+impl<'a> System<'a> for InputFlushSystem {
+    type SystemData = Write<'a, InputState>;
+
+    fn run(&mut self, mut input_state: Self::SystemData) {
+        input_state.flush();
+    }
+}
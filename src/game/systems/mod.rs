@@ -0,0 +1,19 @@
+mod animation;
+mod camera;
+mod input;
+mod movement;
+mod player;
+mod remote_entities;
+mod render_position;
+mod trigger;
+mod ui;
+
+pub use self::animation::*;
+pub use self::camera::*;
+pub use self::input::*;
+pub use self::movement::*;
+pub use self::player::*;
+pub use self::remote_entities::*;
+pub use self::render_position::*;
+pub use self::trigger::*;
+pub use self::ui::*;
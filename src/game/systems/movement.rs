@@ -0,0 +1,28 @@
+use specs::prelude::*;
+
+use crate::collision::CollisionGrid;
+use crate::game::components::{Collider, Position, Velocity};
+use crate::game::resources::DeltaTime;
+
+/// Moves entities by their velocity, sliding colliders along blocked tiles
+pub struct MovementSystem;
+
+impl<'a> System<'a> for MovementSystem {
+    type SystemData = (
+        Read<'a, DeltaTime>,
+        Option<Read<'a, CollisionGrid>>,
+        ReadStorage<'a, Velocity>,
+        ReadStorage<'a, Collider>,
+        WriteStorage<'a, Position>,
+    );
+
+    fn run(&mut self, (dt, collision, velocities, colliders, mut positions): Self::SystemData) {
+        for (velocity, collider, position) in (&velocities, colliders.maybe(), &mut positions).join() {
+            let delta = velocity.0 * dt.0;
+            position.0 = match (&collision, collider) {
+                (Some(collision), Some(collider)) => collision.move_and_slide(position.0, collider.half_size, delta),
+                _ => position.0 + delta,
+            };
+        }
+    }
+}
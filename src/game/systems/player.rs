@@ -0,0 +1,182 @@
+use specs::prelude::*;
+use winit::event::{MouseButton, VirtualKeyCode};
+
+use crate::chat::Chat;
+use crate::collision::CollisionGrid;
+use crate::game::components::{Path, PlayerControlled, Position};
+use crate::game::resources::{DeltaTime, Outbox};
+use crate::input::InputState;
+use crate::network::{ClientMessage, Prediction, PLAYER_SPEED};
+use crate::pathfinding;
+use crate::rendering::Camera;
+
+/// Applies WASD movement or the clicked path to the predicted position and sends it to the server
+pub struct PlayerInputSystem;
+
+impl<'a> System<'a> for PlayerInputSystem {
+    type SystemData = (
+        Read<'a, DeltaTime>,
+        Read<'a, InputState>,
+        ReadExpect<'a, Chat>,
+        WriteExpect<'a, Prediction>,
+        Write<'a, Outbox>,
+        Entities<'a>,
+        ReadStorage<'a, PlayerControlled>,
+        WriteStorage<'a, Path>,
+    );
+
+    fn run(
+        &mut self,
+        (dt, input_state, chat, mut prediction, mut outbox, entities, players, mut paths): Self::SystemData,
+    ) {
+        // Typed text must not move the player
+        if chat.is_focused() {
+            return;
+        }
+
+        let keyboard = input_state.keyboard();
+
+        let mut direction = glm::vec2(0.0, 0.0);
+        let mut moved = false;
+        if keyboard.is_pressed(VirtualKeyCode::D) {
+            direction += glm::vec2(1.0, 0.0);
+            moved = true;
+        } else if keyboard.is_pressed(VirtualKeyCode::A) {
+            direction += glm::vec2(-1.0, 0.0);
+            moved = true;
+        }
+        if keyboard.is_pressed(VirtualKeyCode::W) {
+            direction += glm::vec2(0.0, -1.0);
+            moved = true;
+        } else if keyboard.is_pressed(VirtualKeyCode::S) {
+            direction += glm::vec2(0.0, 1.0);
+            moved = true;
+        }
+
+        if moved {
+            // Walking with the keyboard cancels click-to-move
+            for (entity, _) in (&entities, &players).join() {
+                paths.remove(entity);
+            }
+
+            let command = prediction.apply_input(direction, dt.0);
+            outbox.push(ClientMessage::Input(command));
+            return;
+        }
+
+        let mut finished = Vec::new();
+        for (entity, _, path) in (&entities, &players, &mut paths).join() {
+            let direction = match steer(path, prediction.position(), dt.0) {
+                Some(direction) => direction,
+                None => {
+                    finished.push(entity);
+                    continue;
+                }
+            };
+
+            let previous = *prediction.position();
+            let command = prediction.apply_input(direction, dt.0);
+            outbox.push(ClientMessage::Input(command));
+
+            // Something blocks the way, e.g. the map changed since the path was found
+            if glm::distance(&previous, prediction.position()) < MIN_PROGRESS {
+                finished.push(entity);
+            }
+        }
+        for entity in finished {
+            paths.remove(entity);
+        }
+    }
+}
+
+/// Direction towards the next waypoint, shortened on the last step to stop right at it
+fn steer(path: &mut Path, position: &glm::Vec2, dt: f32) -> Option<glm::Vec2> {
+    while let Some(waypoint) = path.waypoints.front() {
+        let offset = waypoint - position;
+        let distance = glm::length(&offset);
+        if distance < WAYPOINT_RADIUS {
+            path.waypoints.pop_front();
+            continue;
+        }
+
+        let step = PLAYER_SPEED * dt;
+        return Some(offset / distance.max(step));
+    }
+    None
+}
+
+/// Finds a path for the local player to the tile clicked with the left mouse button
+pub struct ClickToMoveSystem;
+
+impl<'a> System<'a> for ClickToMoveSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, InputState>,
+        ReadExpect<'a, Chat>,
+        ReadExpect<'a, Camera>,
+        Option<Read<'a, CollisionGrid>>,
+        ReadStorage<'a, PlayerControlled>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Path>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, input_state, chat, camera, collision, players, positions, mut paths): Self::SystemData,
+    ) {
+        // Clicks while typing don't move the player either
+        if chat.is_focused() || !input_state.mouse().was_pressed(MouseButton::Left) {
+            return;
+        }
+
+        // Paths can't be found until the map is known
+        let collision = match &collision {
+            Some(collision) => collision,
+            None => return,
+        };
+
+        let cursor = input_state.mouse_position().current();
+        let target = camera.screen_to_world(&glm::vec2(cursor.x as f32, cursor.y as f32));
+        let goal = (target.x.floor() as i32, target.y.floor() as i32);
+
+        for (entity, _, position) in (&entities, &players, &positions).join() {
+            let start = (position.0.x.floor() as i32, position.0.y.floor() as i32);
+
+            match pathfinding::find_path(collision, start, goal) {
+                Some(tiles) => {
+                    let waypoints = tiles
+                        .into_iter()
+                        .map(|(x, y)| glm::vec2(x as f32 + 0.5, y as f32 + 0.5))
+                        .collect();
+                    let _ = paths.insert(entity, Path { waypoints });
+                }
+                None => {
+                    log::debug!("No path from {:?} to {:?}", start, goal);
+                    paths.remove(entity);
+                }
+            }
+        }
+    }
+}
+
+/// Moves the local player to the predicted position
+pub struct PlayerSyncSystem;
+
+impl<'a> System<'a> for PlayerSyncSystem {
+    type SystemData = (
+        ReadExpect<'a, Prediction>,
+        ReadStorage<'a, PlayerControlled>,
+        WriteStorage<'a, Position>,
+    );
+
+    fn run(&mut self, (prediction, players, mut positions): Self::SystemData) {
+        for (_, position) in (&players, &mut positions).join() {
+            position.0 = *prediction.position();
+        }
+    }
+}
+
+/// Distance in tiles at which a waypoint counts as reached
+const WAYPOINT_RADIUS: f32 = 1e-3;
+/// Movement per tick in tiles below which the path is given up
+const MIN_PROGRESS: f32 = 1e-4;
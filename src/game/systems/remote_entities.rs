@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use specs::prelude::*;
+
+use crate::game::components::{Animator, Character, Position, PreviousPosition, Remote, Sprite};
+use crate::game::sprites::{PLAYER_CHARACTER, PLAYER_SPRITE};
+use crate::network::{Interpolation, ServerClock};
+
+/// Advances the interpolation render time along the server clock
+pub struct InterpolationSystem;
+
+impl<'a> System<'a> for InterpolationSystem {
+    type SystemData = (ReadExpect<'a, ServerClock>, WriteExpect<'a, Interpolation>);
+
+    fn run(&mut self, (clock, mut interpolation): Self::SystemData) {
+        interpolation.update(&clock);
+    }
+}
+
+/// Spawns, moves and deletes entities replicated from the server, matched by their `Remote` ids
+pub struct RemoteEntitySystem;
+
+impl<'a> System<'a> for RemoteEntitySystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Interpolation>,
+        WriteStorage<'a, Remote>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, PreviousPosition>,
+        WriteStorage<'a, Sprite>,
+        WriteStorage<'a, Animator>,
+        WriteStorage<'a, Character>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            interpolation,
+            mut remotes,
+            mut positions,
+            mut previous_positions,
+            mut sprites,
+            mut animators,
+            mut characters,
+        ): Self::SystemData,
+    ) {
+        let mut unmatched = interpolation.positions().collect::<HashMap<_, _>>();
+
+        for (entity, remote) in (&entities, &remotes).join() {
+            match unmatched.remove(&remote.0) {
+                Some(position) => {
+                    let _ = positions.insert(entity, Position(position));
+                }
+                None => {
+                    let _ = entities.delete(entity);
+                }
+            }
+        }
+
+        for (id, position) in unmatched {
+            entities
+                .build_entity()
+                .with(Remote(id), &mut remotes)
+                .with(Position(position), &mut positions)
+                .with(PreviousPosition(position), &mut previous_positions)
+                .with(Sprite::new(PLAYER_SPRITE), &mut sprites)
+                .with(Animator::new(None), &mut animators)
+                .with(
+                    Character {
+                        name: PLAYER_CHARACTER.to_owned(),
+                    },
+                    &mut characters,
+                )
+                .build();
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use specs::prelude::*;
+
+use crate::game::components::{Position, PreviousPosition, RenderPosition};
+use crate::game::resources::TickAlpha;
+
+/// Remembers positions before the tick changes them
+pub struct PreviousPositionSystem;
+
+impl<'a> System<'a> for PreviousPositionSystem {
+    type SystemData = (ReadStorage<'a, Position>, WriteStorage<'a, PreviousPosition>);
+
+    fn run(&mut self, (positions, mut previous_positions): Self::SystemData) {
+        for (position, previous) in (&positions, &mut previous_positions).join() {
+            previous.0 = position.0;
+        }
+    }
+}
+
+/// Blends the last two tick positions so movement stays smooth between ticks
+pub struct RenderPositionSystem;
+
+impl<'a> System<'a> for RenderPositionSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, TickAlpha>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, PreviousPosition>,
+        WriteStorage<'a, RenderPosition>,
+    );
+
+    fn run(&mut self, (entities, alpha, positions, previous_positions, mut render_positions): Self::SystemData) {
+        for (entity, position, previous) in (&entities, &positions, previous_positions.maybe()).join() {
+            let render_position = match previous {
+                Some(previous) => glm::lerp(&previous.0, &position.0, alpha.0),
+                None => position.0,
+            };
+            let _ = render_positions.insert(entity, RenderPosition(render_position));
+        }
+    }
+}
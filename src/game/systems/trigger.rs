@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use specs::prelude::*;
+
+use crate::chat::{Chat, ChatEntry};
+use crate::game::components::{PlayerControlled, Position, Trigger};
+use crate::network::PropertyValue;
+
+/// Posts the `message` property of a trigger to the chat when the local player enters its area
+#[derive(Default)]
+pub struct TriggerSystem {
+    /// Triggers the player was inside during the previous tick
+    entered: HashSet<Entity>,
+}
+
+impl<'a> System<'a> for TriggerSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, Chat>,
+        ReadStorage<'a, PlayerControlled>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Trigger>,
+    );
+
+    fn run(&mut self, (entities, mut chat, players, positions, triggers): Self::SystemData) {
+        let player = match (&players, &positions).join().next() {
+            Some((_, position)) => position.0,
+            None => return,
+        };
+
+        let mut entered = HashSet::with_capacity(self.entered.len());
+        for (entity, trigger, position) in (&entities, &triggers, &positions).join() {
+            let offset = player - position.0;
+            if offset.x.abs() > trigger.half_size.x || offset.y.abs() > trigger.half_size.y {
+                continue;
+            }
+
+            entered.insert(entity);
+            if self.entered.contains(&entity) {
+                continue;
+            }
+
+            if let Some(PropertyValue::String(message)) = trigger.properties.get(MESSAGE_PROPERTY) {
+                chat.push(ChatEntry {
+                    time: Utc::now(),
+                    from: trigger.name.clone(),
+                    text: message.clone(),
+                });
+            }
+        }
+        self.entered = entered;
+    }
+}
+
+const MESSAGE_PROPERTY: &str = "message";
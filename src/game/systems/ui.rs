@@ -0,0 +1,34 @@
+use specs::prelude::*;
+use winit::event::VirtualKeyCode;
+
+use crate::chat::Chat;
+use crate::game::resources::{ExitRequested, Outbox};
+use crate::input::InputState;
+use crate::network::ClientMessage;
+use crate::stats_overlay::StatsOverlay;
+
+/// Handles chat input, overlay toggles and the exit key
+pub struct UiSystem;
+
+impl<'a> System<'a> for UiSystem {
+    type SystemData = (
+        Read<'a, InputState>,
+        WriteExpect<'a, Chat>,
+        WriteExpect<'a, StatsOverlay>,
+        Write<'a, Outbox>,
+        Write<'a, ExitRequested>,
+    );
+
+    fn run(&mut self, (input_state, mut chat, mut stats_overlay, mut outbox, mut exit): Self::SystemData) {
+        let chat_was_focused = chat.is_focused();
+        if let Some(text) = chat.update(&input_state) {
+            outbox.push(ClientMessage::Chat { text });
+        }
+
+        if !chat_was_focused && input_state.keyboard().was_pressed(VirtualKeyCode::Escape) {
+            exit.0 = true;
+        }
+
+        stats_overlay.update(&input_state);
+    }
+}
@@ -0,0 +1,87 @@
+/// Splits elapsed frame time into fixed simulation ticks, carrying the remainder over to the next frame
+pub struct FixedTimestep {
+    tick_duration: f32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(tick_duration: f32) -> Self {
+        Self {
+            tick_duration,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Adds the time of a frame and returns how many ticks fit into it
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        // Slow frames are clamped so the simulation can't fall further and further behind
+        self.accumulator += frame_time.min(MAX_FRAME_TIME);
+
+        let mut ticks = 0;
+        while self.accumulator >= self.tick_duration {
+            if ticks == MAX_TICKS_PER_FRAME {
+                log::warn!("Simulation is running behind, skipping {:.3}s", self.accumulator);
+                self.accumulator %= self.tick_duration;
+                break;
+            }
+
+            self.accumulator -= self.tick_duration;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    /// Duration of a tick in seconds
+    #[inline]
+    pub fn tick_duration(&self) -> f32 {
+        self.tick_duration
+    }
+
+    /// Progress from the last tick to the next one in `[0, 1)`
+    #[inline]
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.tick_duration
+    }
+}
+
+const MAX_FRAME_TIME: f32 = 0.25;
+const MAX_TICKS_PER_FRAME: u32 = 8;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_split_into_ticks() {
+        let tick = 1.0 / 64.0;
+        let mut timestep = FixedTimestep::new(tick);
+
+        assert_eq!(timestep.advance(4.0 * tick), 4);
+        assert_eq!(timestep.alpha(), 0.0);
+
+        // The remainder is carried over to the next frame
+        assert_eq!(timestep.advance(1.5 * tick), 1);
+        assert_eq!(timestep.alpha(), 0.5);
+        assert_eq!(timestep.advance(0.25 * tick), 0);
+        assert_eq!(timestep.alpha(), 0.75);
+        assert_eq!(timestep.advance(0.25 * tick), 1);
+        assert_eq!(timestep.alpha(), 0.0);
+    }
+
+    #[test]
+    fn slow_frames_are_capped() {
+        let tick = 1.0 / 64.0;
+        let mut timestep = FixedTimestep::new(tick);
+
+        assert_eq!(timestep.advance(0.5 * tick), 0);
+
+        // A second long frame is clamped to `MAX_FRAME_TIME`, the ticks which don't fit are skipped
+        assert_eq!(timestep.advance(1.0), MAX_TICKS_PER_FRAME);
+        assert_eq!(timestep.alpha(), 0.5);
+
+        // Skipped ticks don't catch up later
+        assert_eq!(timestep.advance(0.0), 0);
+        assert_eq!(timestep.advance(tick), 1);
+        assert_eq!(timestep.alpha(), 0.5);
+    }
+}
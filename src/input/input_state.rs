@@ -7,6 +7,7 @@ pub struct InputState {
     keyboard: InputStateBuffers<KeyboardState>,
     mouse: InputStateBuffers<MouseButtonsState>,
     mouse_position: MousePosition,
+    text: String,
 }
 
 #[allow(dead_code)]
@@ -16,6 +17,7 @@ impl InputState {
             keyboard: InputStateBuffers::new(),
             mouse: InputStateBuffers::new(),
             mouse_position: MousePosition::new(),
+            text: String::new(),
         }
     }
 
@@ -23,6 +25,7 @@ impl InputState {
         self.keyboard.flush();
         self.mouse.flush();
         self.mouse_position.flush();
+        self.text.clear();
     }
 
     pub fn handle_window_event(&mut self, event: &WindowEvent) {
@@ -44,6 +47,7 @@ impl InputState {
             WindowEvent::CursorMoved { position, .. } => {
                 self.mouse_position.handle_movement(position);
             }
+            WindowEvent::ReceivedCharacter(c) => self.text.push(*c),
             _ => {}
         }
     }
@@ -62,6 +66,18 @@ impl InputState {
     pub fn mouse_position(&self) -> &MousePosition {
         &self.mouse_position
     }
+
+    /// Characters typed since the last flush, including control characters like backspace
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub struct InputStateBuffers<T>
@@ -187,7 +203,7 @@ impl MousePosition {
     }
 
     #[inline]
-    fn current(&self) -> &PhysicalPosition<f64> {
+    pub fn current(&self) -> &PhysicalPosition<f64> {
         &self.current
     }
 
@@ -1,27 +1,42 @@
 extern crate nalgebra_glm as glm;
 
+pub mod animation;
+mod chat;
+pub mod chunks;
+pub mod collision;
 pub mod config;
 mod game;
 mod input;
+pub mod network;
+pub mod pathfinding;
 mod rendering;
 mod resources;
+pub mod server;
+mod stats_overlay;
 
 use std::path::Path;
 
 use anyhow::Result;
-use once_cell::sync::OnceCell;
-use winit::dpi::{LogicalSize, PhysicalSize};
-use winit::event::{Event, VirtualKeyCode, WindowEvent};
+use specs::Join;
+use winit::dpi::LogicalSize;
+use winit::event::{Event, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::WindowBuilder;
 
 use embercore::tme;
 
+use crate::animation::{Animations, TileAnimationTable};
+use crate::chat::{Chat, ChatEntry};
 use crate::config::Config;
+use crate::game::{ExitRequested, Game, MapSize, Outbox, RenderPosition, Sprite};
 use crate::input::InputState;
+use crate::network::*;
 use crate::rendering::*;
+use crate::stats_overlay::StatsOverlay;
+
+pub async fn run(config: Config) -> Result<()> {
+    let mut network = NetworkClient::spawn(&config);
 
-pub async fn run(_config: Config) -> Result<()> {
     let events_loop = EventLoop::new();
     let window = WindowBuilder::new()
         .with_min_inner_size(LogicalSize::new(800, 600))
@@ -44,89 +59,43 @@ pub async fn run(_config: Config) -> Result<()> {
         move || {
             let content_dir = Path::new("content");
 
-            let map = match resources::load_json(&content_dir.join("tilemap.json")).unwrap() {
-                tme::Map::Orthogonal(map) => map,
-                _ => panic!("Unsupported map type"),
-            };
-
-            let tileset_first_gid = match map.tile_sets.first() {
-                Some(tme::TilesetContainer::TilesetRef(tileset_ref)) => {
-                    let tileset = resources::load_json::<tme::Tileset>(&content_dir.join(&tileset_ref.source)).unwrap();
-                    let (texture_view, size) =
-                        load_tileset(&device, &queue, &content_dir.join(&tileset.image.unwrap()));
-
-                    let _ = tx.send(ResourcesEvent::TileSetLoaded { texture_view, size });
-
-                    tileset_ref.first_gid
-                }
-                _ => panic!("No tilesets found"),
-            };
-
-            let chunks_in_column = (map.height as usize + CHUNK_SIZE - 1) / CHUNK_SIZE;
-            let chunks_in_row = (map.width as usize + CHUNK_SIZE - 1) / CHUNK_SIZE;
-
-            let mut chunk = Vec::<u16>::new();
-            chunk.resize(CHUNK_SIZE * CHUNK_SIZE, 0);
-
-            let slice_offset_matrix_size = std::mem::size_of::<f32>() * 4 * 4;
-            let slice_tiles_array_size = std::mem::size_of::<u16>() * chunk.len();
-
-            let mut slice_buffer = Vec::<u8>::new();
-            slice_buffer.resize(slice_offset_matrix_size + slice_tiles_array_size, 0);
-
-            for layer in map.layers.iter().filter_map(|item| {
-                if let tme::Layer::TileLayer(tile_layer) = item {
-                    Some(tile_layer)
-                } else {
-                    None
-                }
-            }) {
-                let tiles = layer.data.extract_tiles(layer.compression).unwrap();
-
-                for chunk_y in (0..chunks_in_column).map(|i| i * CHUNK_SIZE) {
-                    for chunk_x in (0..chunks_in_row).map(|i| i * CHUNK_SIZE) {
-                        let offset = glm::translation(&glm::vec3(chunk_x as f32, chunk_y as f32, 0.0));
-                        slice_buffer[..slice_offset_matrix_size]
-                            .copy_from_slice(bytemuck::cast_slice(offset.as_slice()));
-
-                        let _ = chunk.iter_mut().map(|item| *item = 0).count();
-
-                        let max_y = std::cmp::min(chunk_y + CHUNK_SIZE, map.height as usize) - chunk_y;
-                        let max_x = std::cmp::min(chunk_x + CHUNK_SIZE, map.width as usize) - chunk_x;
+            let tileset = resources::load_json::<tme::Tileset>(&content_dir.join("tileset.json")).unwrap();
+            let (texture_view, size) = load_texture(&device, &queue, &content_dir.join(&tileset.image.unwrap()));
+            let animations = Animations::load(&content_dir.join("tileset.json")).unwrap();
 
-                        for y in 0..max_y {
-                            for x in 0..max_x {
-                                let tile_index = (chunk_y + y) * map.width as usize + (chunk_x + x);
-                                chunk[y * CHUNK_SIZE + x] = (tiles[tile_index] + 1 - tileset_first_gid) as u16;
-                            }
-                        }
-                        slice_buffer[slice_offset_matrix_size..].copy_from_slice(bytemuck::cast_slice(&chunk));
+            let _ = tx.send(ResourcesEvent::TileSetLoaded {
+                texture_view,
+                size,
+                animations,
+            });
 
-                        let buffer = device.create_buffer_with_data(
-                            slice_buffer.as_slice(),
-                            wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
-                        );
+            let (texture_view, _) = load_texture(&device, &queue, &content_dir.join("font.png"));
 
-                        let _ = tx.send(ResourcesEvent::ChunkLoaded { offset, buffer });
-                    }
-                }
-            }
+            let _ = tx.send(ResourcesEvent::FontLoaded { texture_view });
         }
     });
 
     //
 
-    let mut camera = Camera::new(window.inner_size());
-    camera.set_view(&(glm::scaling(&glm::vec3(32.0, 32.0, 1.0)) * glm::translation(&glm::vec3(-8.0, -8.0, 0.0))));
-    rendering_state
-        .tilemap_renderer()
-        .update_camera(&device, &camera.view, &camera.projection);
+    let mut game = Game::new(&config);
+    // The player is placed at the position sent with the map
+    game.world_mut().insert(Prediction::new(glm::vec2(0.0, 0.0)));
+    game.world_mut().insert(Interpolation::new(
+        config.interpolation_delay(),
+        config.max_extrapolation(),
+    ));
+    game.world_mut().insert(network.clock().clone());
+    game.world_mut().insert(Chat::new());
+    game.world_mut().insert(StatsOverlay::new());
 
-    let mut input_state = InputState::new();
+    let camera = Camera::new(window.inner_size());
+    update_camera(&mut rendering_state, &device, &camera);
+    game.world_mut().insert(camera);
 
-    let mut chunks = Vec::new();
+    let mut tile_layers = TileLayers::new();
 
-    let mut now = std::time::Instant::now();
+    let start_time = std::time::Instant::now();
+    let mut now = start_time;
 
     events_loop.run(move |event, _, control_flow| {
         match event {
@@ -140,30 +109,94 @@ pub async fn run(_config: Config) -> Result<()> {
                 event: WindowEvent::Resized(size),
                 ..
             } => {
+                let mut camera = game.world().write_resource::<Camera>();
                 camera.update_projection(size);
                 rendering_state.handle_resize(size);
-                rendering_state
-                    .tilemap_renderer()
-                    .update_camera(&device, &camera.view, &camera.projection);
+                update_camera(&mut rendering_state, &device, &camera);
             }
             Event::WindowEvent { ref event, .. } => {
-                input_state.handle_window_event(event);
+                game.world().write_resource::<InputState>().handle_window_event(event);
             }
             Event::RedrawEventsCleared => {
                 while let Ok(resources_event) = rx.try_recv() {
                     match resources_event {
-                        ResourcesEvent::TileSetLoaded { texture_view, size } => {
+                        ResourcesEvent::TileSetLoaded {
+                            texture_view,
+                            size,
+                            animations,
+                        } => {
                             rendering_state
                                 .tilemap_renderer()
                                 .update_tileset(&device, &texture_view, &size);
-                        }
-                        ResourcesEvent::ChunkLoaded { buffer, .. } => {
-                            let bind_group = rendering_state
+                            rendering_state
+                                .sprite_renderer()
+                                .update_atlas(&device, &texture_view, &size);
+                            rendering_state
                                 .tilemap_renderer()
-                                .create_chunk_bind_group(&device, &buffer);
+                                .update_animations(&device, &TileAnimationTable::from_animations(&animations));
+                            game.world_mut().insert(animations);
+                        }
+                        ResourcesEvent::FontLoaded { texture_view } => {
+                            rendering_state.text_renderer().update_font(&device, &texture_view);
+                        }
+                    }
+                }
 
-                            chunks.push((buffer, bind_group));
+                while let Some(network_event) = network.try_recv() {
+                    match network_event {
+                        NetworkEvent::StateChanged(state) => {
+                            log::info!("Connection state: {:?}", state);
+                            window.set_title(&window_title(&state));
+                        }
+                        NetworkEvent::Message(ServerMessage::PlayerState { last_input, position }) => {
+                            game.world()
+                                .write_resource::<Prediction>()
+                                .reconcile(last_input, glm::vec2(position[0], position[1]));
+                        }
+                        NetworkEvent::Snapshot(snapshot) => {
+                            let mut interpolation = game.world().write_resource::<Interpolation>();
+                            for entity in snapshot.entities.values() {
+                                let position = glm::vec2(entity.position[0], entity.position[1]);
+                                interpolation.push(snapshot.time, entity.id, position);
+                            }
+                            interpolation.retain(|entity| snapshot.entities.contains_key(&entity));
+                        }
+                        NetworkEvent::Message(ServerMessage::MapInfo {
+                            width,
+                            height,
+                            collision,
+                            objects,
+                            y_sorted_layers,
+                            player_position,
+                        }) => {
+                            game.world().write_resource::<MapSize>().0 = Some(glm::vec2(width as f32, height as f32));
+                            game.world().write_resource::<Prediction>().set_collision(collision.clone());
+                            game.world_mut().insert(collision);
+                            game.load_objects(&objects);
+                            tile_layers.set_y_sorted_layers(&y_sorted_layers);
+
+                            let player_position = glm::vec2(player_position[0], player_position[1]);
+                            game.world().write_resource::<Prediction>().reset(player_position);
+                            game.place_player(player_position);
+                            game.world().write_resource::<Camera>().look_at(&player_position);
+                        }
+                        NetworkEvent::Message(message @ ServerMessage::ChunkLoaded(_))
+                        | NetworkEvent::Message(message @ ServerMessage::ChunkUnloaded { .. }) => {
+                            tile_layers.apply(&message, |chunk| {
+                                let buffer = create_chunk_buffer(&device, chunk);
+                                let bind_group = rendering_state
+                                    .tilemap_renderer()
+                                    .create_chunk_bind_group(&device, &buffer);
+                                (buffer, bind_group)
+                            });
                         }
+                        NetworkEvent::Message(ServerMessage::Chat { from, text, time }) => {
+                            game.world()
+                                .write_resource::<Chat>()
+                                .push(ChatEntry { time, from, text });
+                        }
+                        NetworkEvent::Message(message) => log::debug!("Received message: {:?}", message),
+                        NetworkEvent::Failed(e) => log::error!("Network error: {}", e),
                     }
                 }
 
@@ -171,35 +204,53 @@ pub async fn run(_config: Config) -> Result<()> {
                 let dt = (then - now).as_secs_f32();
                 now = then;
 
-                if input_state.keyboard().was_pressed(VirtualKeyCode::Escape) {
-                    *control_flow = ControlFlow::Exit;
-                }
+                let view = game.world().read_resource::<Camera>().view;
+
+                game.update(dt);
 
-                let speed = 10.0;
-                let mut direction = glm::vec3(0.0, 0.0, 0.0);
-                let mut moved = false;
-                if input_state.keyboard().is_pressed(VirtualKeyCode::D) {
-                    direction += glm::vec3(1.0, 0.0, 0.0);
-                    moved = true;
-                } else if input_state.keyboard().is_pressed(VirtualKeyCode::A) {
-                    direction += glm::vec3(-1.0, 0.0, 0.0);
-                    moved = true;
+                for message in game.world().write_resource::<Outbox>().drain() {
+                    network.send(message);
                 }
-                if input_state.keyboard().is_pressed(VirtualKeyCode::W) {
-                    direction += glm::vec3(0.0, -1.0, 0.0);
-                    moved = true;
-                } else if input_state.keyboard().is_pressed(VirtualKeyCode::S) {
-                    direction += glm::vec3(0.0, 1.0, 0.0);
-                    moved = true;
+
+                if game.world().read_resource::<ExitRequested>().0 {
+                    *control_flow = ControlFlow::Exit;
                 }
-                if moved {
-                    camera.set_view(&(camera.view * glm::translation(&(-direction * dt * speed))));
-                    rendering_state
-                        .tilemap_renderer()
-                        .update_camera(&device, &camera.view, &camera.projection);
+
+                let camera = game.world().read_resource::<Camera>();
+                if camera.view != view {
+                    update_camera(&mut rendering_state, &device, &camera);
                 }
+                drop(camera);
 
-                input_state.flush(); // TODO: maybe move into ecs?
+                rendering_state
+                    .tilemap_renderer()
+                    .update_time(&queue, now - start_time);
+
+                let mut sprites = SpriteBatch::new();
+                for (position, sprite) in (
+                    &game.world().read_storage::<RenderPosition>(),
+                    &game.world().read_storage::<Sprite>(),
+                )
+                    .join()
+                {
+                    sprites.push(
+                        [position.0.x, position.0.y],
+                        sprite.frame,
+                        sprite.tint,
+                        [sprite.flip_x, sprite.flip_y],
+                    );
+                }
+                sprites.sort_by_y();
+                let sprites = rendering_state.sprite_renderer().create_sprite_buffer(&device, &sprites);
+
+                let mut ui_text = TextBatch::new();
+                game.world()
+                    .read_resource::<Chat>()
+                    .layout(&mut ui_text, window.inner_size());
+                game.world()
+                    .read_resource::<StatsOverlay>()
+                    .layout(&mut ui_text, network.stats());
+                let ui_text = rendering_state.text_renderer().create_text_buffer(&device, &ui_text);
 
                 let (mut encoder, mut frame) = rendering_state.frame();
 
@@ -208,9 +259,19 @@ pub async fn run(_config: Config) -> Result<()> {
                         Pass::World(cx) => {
                             let mut pass = cx.start(&mut encoder);
 
-                            let mut tilemap_renderer = cx.tile_map_renderer().start(&mut pass);
-                            for (_, chunk) in chunks.iter() {
-                                tilemap_renderer.draw_chunk(chunk);
+                            tile_layers.draw(
+                                &mut pass,
+                                cx.tile_map_renderer(),
+                                cx.sprite_renderer(),
+                                sprites.as_ref(),
+                            );
+                        }
+                        Pass::Ui(cx) => {
+                            let mut pass = cx.start(&mut encoder);
+
+                            let mut text_renderer = cx.text_renderer().start(&mut pass);
+                            if let Some(text) = &ui_text {
+                                text_renderer.draw_text(text);
                             }
                         }
                     }
@@ -223,7 +284,7 @@ pub async fn run(_config: Config) -> Result<()> {
     })
 }
 
-fn load_tileset(
+fn load_texture(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
     path: &std::path::PathBuf,
@@ -251,63 +312,47 @@ fn load_tileset(
     (texture.create_default_view(), [texture_info.width, texture_info.height])
 }
 
-struct Camera {
-    view: glm::Mat4,
-    projection: glm::Mat4,
-    scale: u32,
+fn update_camera(rendering_state: &mut RenderingState, device: &wgpu::Device, camera: &Camera) {
+    rendering_state
+        .tilemap_renderer()
+        .update_camera(device, &camera.view, &camera.projection);
+    rendering_state
+        .sprite_renderer()
+        .update_camera(device, &camera.view, &camera.projection);
 }
 
-impl Camera {
-    pub fn new(size: PhysicalSize<u32>) -> Self {
-        let mut camera = Self {
-            view: glm::identity(),
-            projection: glm::identity(),
-            scale: 2,
-        };
-        camera.update_projection(size);
-        camera
+fn window_title(state: &ConnectionState) -> String {
+    match state {
+        ConnectionState::Connecting => "embercore - connecting...".to_owned(),
+        ConnectionState::Connected { .. } => "embercore".to_owned(),
+        ConnectionState::Reconnecting { attempt, .. } => format!("embercore - reconnecting (attempt {})...", attempt),
+        ConnectionState::Disconnected => "embercore - disconnected".to_owned(),
     }
+}
 
-    #[inline]
-    pub fn set_view(&mut self, view: &glm::Mat4) {
-        self.view.copy_from(view);
-    }
+fn create_chunk_buffer(device: &wgpu::Device, chunk: &ChunkData) -> wgpu::Buffer {
+    let offset = glm::translation(&glm::vec3(
+        (chunk.x * CHUNK_SIZE as i32) as f32,
+        (chunk.y * CHUNK_SIZE as i32) as f32,
+        0.0,
+    ));
 
-    #[inline]
-    pub fn update_projection(&mut self, size: PhysicalSize<u32>) {
-        let (width, height) = (size.width, size.height);
-        let factor = 2.0 * self.scale as f32;
-
-        #[cfg_attr(rustfmt, rustfmt_skip)]
-        let correction_matrix = OPENGL_TO_WGPU_MATRIX.get_or_init(|| glm::mat4(
-            1.0, 0.0, 0.0, 0.0,
-            0.0, -1.0, 0.0, 0.0,
-            0.0, 0.0, 1.0, 0.0,
-            0.0, 0.0, 0.0, 1.0,
-        ));
-
-        self.projection = correction_matrix
-            * glm::ortho(
-                -(width as f32 / factor),
-                width as f32 / factor,
-                -(height as f32 / factor),
-                height as f32 / factor,
-                -10.0,
-                10.0,
-            );
-    }
+    let mut data = Vec::<u8>::with_capacity(std::mem::size_of::<glm::Mat4>() + chunk.tiles.len() * 2);
+    data.extend_from_slice(bytemuck::cast_slice(offset.as_slice()));
+    data.extend_from_slice(bytemuck::cast_slice(&chunk.tiles));
+
+    device.create_buffer_with_data(&data, wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST)
 }
 
 enum ResourcesEvent {
     TileSetLoaded {
         texture_view: wgpu::TextureView,
         size: [u32; 2],
+        animations: Animations,
     },
-    ChunkLoaded {
-        offset: glm::Mat4,
-        buffer: wgpu::Buffer,
+    FontLoaded {
+        texture_view: wgpu::TextureView,
     },
 }
 
-static OPENGL_TO_WGPU_MATRIX: OnceCell<glm::Mat4> = OnceCell::new();
-const CHUNK_SIZE: usize = 16;
+pub const CHUNK_SIZE: usize = 16;
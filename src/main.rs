@@ -4,6 +4,8 @@ use embercore_client_lib::config::Config;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+
     let config = Config::new("configs/client.local.yml")?;
 
     embercore_client_lib::run(config).await?;
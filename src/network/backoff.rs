@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Backoff {
+    /// Delays are at least `MIN_DELAY`, so the randomized range is never empty
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        let initial = initial.max(MIN_DELAY);
+        Self {
+            initial,
+            max: max.max(initial),
+            attempt: 0,
+        }
+    }
+
+    /// Returns the delay before the next attempt: exponential growth capped at `max`,
+    /// randomized into `[delay / 2, delay)` so clients don't reconnect in lockstep
+    pub fn next_delay(&mut self) -> Duration {
+        let factor = 2u32.saturating_pow(self.attempt);
+        let delay = self.initial.checked_mul(factor).unwrap_or(self.max).min(self.max);
+        self.attempt = self.attempt.saturating_add(1);
+
+        let delay = delay.as_secs_f64();
+        Duration::from_secs_f64(rand::thread_rng().gen_range(delay / 2.0, delay))
+    }
+
+    #[inline]
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    #[inline]
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+}
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
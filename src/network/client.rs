@@ -0,0 +1,318 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use super::backoff::Backoff;
+use super::clock::ServerClock;
+use super::connection::Connection;
+use super::error::Error;
+use super::protocol::{ClientMessage, ServerMessage};
+use super::replay::{Recorder, Replay, ReplayEntry};
+use super::snapshot::{Snapshot, SnapshotReceiver};
+use super::stats::NetStats;
+use crate::config::Config;
+
+pub struct NetworkClient {
+    outbound: mpsc::UnboundedSender<ClientMessage>,
+    inbound: mpsc::UnboundedReceiver<NetworkEvent>,
+    clock: ServerClock,
+    stats: NetStats,
+}
+
+impl NetworkClient {
+    pub fn spawn(config: &Config) -> Self {
+        let (outbound_tx, outbound_rx) = mpsc::unbounded_channel();
+        let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+
+        let clock = ServerClock::new();
+        let stats = NetStats::new();
+
+        match &config.replay_file {
+            Some(path) => tokio::spawn(run_replay(path.clone(), clock.clone(), stats.clone(), inbound_tx)),
+            None => tokio::spawn(
+                ClientTask {
+                    config: config.clone(),
+                    clock: clock.clone(),
+                    stats: stats.clone(),
+                    outbound: outbound_rx,
+                    inbound: inbound_tx,
+                    session: None,
+                    backoff: Backoff::new(RECONNECT_INITIAL_DELAY, RECONNECT_MAX_DELAY),
+                    recorder: None,
+                    snapshots: SnapshotReceiver::new(),
+                }
+                .run(),
+            ),
+        };
+
+        Self {
+            outbound: outbound_tx,
+            inbound: inbound_rx,
+            clock,
+            stats,
+        }
+    }
+
+    #[inline]
+    pub fn send(&self, message: ClientMessage) {
+        let _ = self.outbound.send(message);
+    }
+
+    #[inline]
+    pub fn try_recv(&mut self) -> Option<NetworkEvent> {
+        self.inbound.try_recv().ok()
+    }
+
+    #[inline]
+    pub fn clock(&self) -> &ServerClock {
+        &self.clock
+    }
+
+    #[inline]
+    pub fn stats(&self) -> &NetStats {
+        &self.stats
+    }
+}
+
+#[derive(Debug)]
+pub enum NetworkEvent {
+    StateChanged(ConnectionState),
+    Message(ServerMessage),
+    Snapshot(Snapshot),
+    Failed(Error),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected { session: Uuid },
+    Reconnecting { attempt: u32, delay: Duration },
+    Disconnected,
+}
+
+struct ClientTask {
+    config: Config,
+    clock: ServerClock,
+    stats: NetStats,
+    outbound: mpsc::UnboundedReceiver<ClientMessage>,
+    inbound: mpsc::UnboundedSender<NetworkEvent>,
+    session: Option<Uuid>,
+    backoff: Backoff,
+    recorder: Option<Recorder>,
+    snapshots: SnapshotReceiver,
+}
+
+impl ClientTask {
+    async fn run(mut self) {
+        if let Some(path) = &self.config.record_file {
+            match Recorder::create(path).await {
+                Ok(recorder) => self.recorder = Some(recorder),
+                Err(e) => log::error!("Failed to create replay file {}: {:?}", path.display(), e),
+            }
+        }
+
+        let _ = self
+            .inbound
+            .send(NetworkEvent::StateChanged(ConnectionState::Connecting));
+
+        loop {
+            match self.run_connection().await {
+                Ok(()) => break,
+                Err(e) => {
+                    log::error!("Connection to {} lost: {:?}", self.config.server_address, e);
+
+                    match e.downcast::<Error>() {
+                        Ok(e @ Error::ProtocolVersionMismatch { .. }) | Ok(e @ Error::InvalidCredentials) => {
+                            let _ = self.inbound.send(NetworkEvent::Failed(e));
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            let delay = self.backoff.next_delay();
+            let state = ConnectionState::Reconnecting {
+                attempt: self.backoff.attempt(),
+                delay,
+            };
+            if self.inbound.send(NetworkEvent::StateChanged(state)).is_err() {
+                break;
+            }
+
+            tokio::time::delay_for(delay).await;
+        }
+
+        let _ = self
+            .inbound
+            .send(NetworkEvent::StateChanged(ConnectionState::Disconnected));
+    }
+
+    /// Runs a single connection until it fails or the client is dropped.
+    ///
+    /// Outbound messages queued while disconnected stay in `outbound` and are sent after the next login
+    async fn run_connection(&mut self) -> Result<()> {
+        let address = self.config.server_address;
+        let timeout = self.config.timeout();
+
+        let mut connection = Connection::connect(address, self.config.transport, timeout).await?;
+        connection.set_recorder(self.recorder.clone());
+        connection.set_stats(Some(self.stats.clone()));
+
+        // A server that accepted the connection but never answers must not stall reconnecting
+        let handshake = connection.handshake(self.config.credentials.clone(), self.session);
+        let session = tokio::time::timeout(timeout, handshake)
+            .await
+            .map_err(|_| Error::Timeout(timeout))??;
+        match self.session {
+            Some(previous) if previous == session => log::info!("Resumed session {} on {}", session, address),
+            _ => log::info!("Connected to {}, session {}", address, session),
+        }
+        self.session = Some(session);
+        self.backoff.reset();
+        self.snapshots.reset();
+
+        let state = ConnectionState::Connected { session };
+        if self.inbound.send(NetworkEvent::StateChanged(state)).is_err() {
+            return Ok(());
+        }
+
+        let mut ping_interval = tokio::time::interval(PING_INTERVAL);
+        let mut ping_id = 0;
+
+        // Pings keep the server talking, so silence longer than the timeout means the link is dead
+        let mut last_received = Instant::now();
+
+        loop {
+            tokio::select! {
+                message = connection.receive() => {
+                    let message = message?;
+                    last_received = Instant::now();
+
+                    match message {
+                        ServerMessage::Pong { client_time, server_time, .. } => {
+                            let now = Utc::now();
+                            self.clock.update(client_time, server_time, now);
+                            self.stats.record_rtt((now - client_time).to_std().unwrap_or_default());
+                        }
+                        ServerMessage::Snapshot(delta) => match self.snapshots.receive(delta) {
+                            Ok(Some(snapshot)) => {
+                                connection.send(ClientMessage::SnapshotAck { sequence: snapshot.sequence }).await?;
+                                if self.inbound.send(NetworkEvent::Snapshot(snapshot.clone())).is_err() {
+                                    return Ok(());
+                                }
+                            }
+                            Ok(None) => self.stats.record_late(1),
+                            Err(e) => {
+                                if self.snapshots.request_full_snapshot() {
+                                    log::warn!("{}, requesting full snapshot", e);
+                                    connection.send(ClientMessage::RequestFullSnapshot).await?;
+                                }
+                            }
+                        },
+                        message => {
+                            if self.inbound.send(NetworkEvent::Message(message)).is_err() {
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+                message = self.outbound.recv() => match message {
+                    Some(message) => connection.send(message).await?,
+                    None => return Ok(()),
+                },
+                _ = ping_interval.tick() => {
+                    connection.send(ClientMessage::Ping { id: ping_id, client_time: Utc::now() }).await?;
+                    ping_id = ping_id.wrapping_add(1);
+
+                    log::debug!("Network stats: {}", self.stats.report());
+                }
+                _ = tokio::time::delay_until(last_received + timeout) => {
+                    return Err(Error::Timeout(timeout).into());
+                }
+            }
+        }
+    }
+}
+
+/// Plays a recorded session back as if it was received from the server
+async fn run_replay(path: PathBuf, clock: ServerClock, stats: NetStats, inbound: mpsc::UnboundedSender<NetworkEvent>) {
+    if let Err(e) = play_replay(&path, &clock, &stats, &inbound).await {
+        log::error!("Failed to play replay {}: {:?}", path.display(), e);
+    }
+
+    let _ = inbound.send(NetworkEvent::StateChanged(ConnectionState::Disconnected));
+}
+
+async fn play_replay(
+    path: &Path,
+    clock: &ServerClock,
+    stats: &NetStats,
+    inbound: &mpsc::UnboundedSender<NetworkEvent>,
+) -> Result<()> {
+    let mut replay = Replay::open(path).await?;
+
+    let started_at = tokio::time::Instant::now();
+    let started_at_utc = Utc::now();
+    let mut recorded_at = None;
+
+    let mut snapshots = SnapshotReceiver::new();
+
+    while let Some(entry) = replay.next().await? {
+        let (time, message) = match entry {
+            ReplayEntry::Inbound { time, message } => (time, message),
+            ReplayEntry::Outbound { .. } => continue,
+        };
+
+        // Keep the recorded pacing between messages
+        let recorded_at = *recorded_at.get_or_insert(time);
+        tokio::time::delay_until(started_at + (time - recorded_at).to_std().unwrap_or_default()).await;
+
+        let shift = started_at_utc - recorded_at;
+
+        let event = match message {
+            ServerMessage::Welcome { session, .. } => {
+                snapshots.reset();
+                NetworkEvent::StateChanged(ConnectionState::Connected { session })
+            }
+            ServerMessage::Pong {
+                client_time,
+                server_time,
+                ..
+            } => {
+                // Shift local times so that the server clock maps to the recorded server time
+                clock.update(client_time + shift, server_time, time + shift);
+                stats.record_rtt((time - client_time).to_std().unwrap_or_default());
+                continue;
+            }
+            ServerMessage::Snapshot(delta) => match snapshots.receive(delta) {
+                Ok(Some(snapshot)) => NetworkEvent::Snapshot(snapshot.clone()),
+                Ok(None) => {
+                    stats.record_late(1);
+                    continue;
+                }
+                Err(e) => {
+                    // The recorded session has requested a full snapshot which will follow
+                    log::warn!("{}", e);
+                    continue;
+                }
+            },
+            message => NetworkEvent::Message(message),
+        };
+
+        if inbound.send(event).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
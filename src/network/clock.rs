@@ -0,0 +1,87 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// Estimated server time, synchronized with NTP-style ping exchanges.
+///
+/// Cheap to clone, all clones share the same estimate
+#[derive(Clone, Default)]
+pub struct ServerClock {
+    state: Arc<Mutex<ClockState>>,
+}
+
+#[derive(Default)]
+struct ClockState {
+    offset: Option<f64>,
+    rtt: Option<f64>,
+}
+
+impl ServerClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies a single ping sample.
+    ///
+    /// Assumes symmetric latency, so the server is expected to have stamped `server_time`
+    /// halfway between `client_sent` and `client_received`
+    pub fn update(&self, client_sent: DateTime<Utc>, server_time: DateTime<Utc>, client_received: DateTime<Utc>) {
+        let rtt = seconds(client_received - client_sent).max(0.0);
+        let offset = seconds(server_time - client_sent) - rtt / 2.0;
+
+        let mut state = self.state.lock().unwrap();
+        state.rtt = Some(smooth(state.rtt, rtt));
+        state.offset = Some(smooth(state.offset, offset));
+    }
+
+    #[inline]
+    pub fn is_synchronized(&self) -> bool {
+        self.state.lock().unwrap().offset.is_some()
+    }
+
+    /// Estimated current server time. Falls back to local time before the first sample
+    pub fn now(&self) -> DateTime<Utc> {
+        self.to_server(Utc::now())
+    }
+
+    /// Estimated current server time in seconds since unix epoch
+    pub fn timestamp(&self) -> f64 {
+        timestamp(self.now())
+    }
+
+    pub fn to_server(&self, local_time: DateTime<Utc>) -> DateTime<Utc> {
+        local_time + self.offset()
+    }
+
+    pub fn to_local(&self, server_time: DateTime<Utc>) -> DateTime<Utc> {
+        server_time - self.offset()
+    }
+
+    pub fn offset(&self) -> chrono::Duration {
+        let offset = self.state.lock().unwrap().offset.unwrap_or_default();
+        chrono::Duration::microseconds((offset * 1e6) as i64)
+    }
+
+    pub fn rtt(&self) -> Option<Duration> {
+        self.state.lock().unwrap().rtt.map(Duration::from_secs_f64)
+    }
+}
+
+/// Converts time into seconds since unix epoch, the representation used in snapshots
+pub fn timestamp(time: DateTime<Utc>) -> f64 {
+    time.timestamp() as f64 + time.timestamp_subsec_nanos() as f64 * 1e-9
+}
+
+fn seconds(duration: chrono::Duration) -> f64 {
+    duration.num_microseconds().unwrap_or(i64::MAX) as f64 * 1e-6
+}
+
+fn smooth(current: Option<f64>, sample: f64) -> f64 {
+    match current {
+        Some(current) => current + (sample - current) * SMOOTHING_FACTOR,
+        None => sample,
+    }
+}
+
+const SMOOTHING_FACTOR: f64 = 0.1;
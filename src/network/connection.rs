@@ -0,0 +1,174 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio_serde::formats::Bincode;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use uuid::Uuid;
+
+use super::error::Error;
+use super::protocol::*;
+use super::reliability::ChannelMessage;
+use super::replay::Recorder;
+use super::stats::NetStats;
+use super::udp::UdpTransport;
+
+pub type Transport<I, O> = tokio_serde::Framed<Framed<TcpStream, LengthDelimitedCodec>, I, O, Bincode<I, O>>;
+
+pub fn framed<I, O>(stream: TcpStream) -> Transport<I, O> {
+    tokio_serde::Framed::new(Framed::new(stream, LengthDelimitedCodec::new()), Bincode::default())
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    Tcp,
+    Udp,
+}
+
+impl Default for TransportKind {
+    fn default() -> Self {
+        Self::Tcp
+    }
+}
+
+/// Message stream over any of the supported transports
+pub enum Link<I, O> {
+    Tcp(Transport<I, O>),
+    Udp(UdpTransport<I, O>),
+}
+
+impl<I, O> Link<I, O>
+where
+    I: DeserializeOwned + Unpin,
+    O: Serialize + ChannelMessage + Unpin,
+{
+    /// UDP links are lost after `timeout` without any packets, TCP ones when the socket is closed
+    pub async fn connect(address: SocketAddr, kind: TransportKind, timeout: Duration) -> Result<Self> {
+        Ok(match kind {
+            TransportKind::Tcp => {
+                let stream = TcpStream::connect(address).await?;
+                stream.set_nodelay(true)?;
+                Self::Tcp(framed(stream))
+            }
+            TransportKind::Udp => Self::Udp(UdpTransport::connect(address, timeout).await?),
+        })
+    }
+
+    pub async fn send(&mut self, message: O) -> Result<()> {
+        match self {
+            Self::Tcp(transport) => transport.send(message).await?,
+            Self::Udp(transport) => transport.send(message).await?,
+        }
+        Ok(())
+    }
+
+    pub async fn receive(&mut self) -> Result<I> {
+        match self {
+            Self::Tcp(transport) => match transport.next().await {
+                Some(message) => Ok(message?),
+                None => Err(Error::ConnectionClosed.into()),
+            },
+            Self::Udp(transport) => transport.receive().await,
+        }
+    }
+}
+
+pub struct Connection {
+    link: Link<ServerMessage, ClientMessage>,
+    recorder: Option<Recorder>,
+    stats: Option<NetStats>,
+}
+
+impl Connection {
+    pub async fn connect(address: SocketAddr, kind: TransportKind, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            link: Link::connect(address, kind, timeout).await?,
+            recorder: None,
+            stats: None,
+        })
+    }
+
+    #[inline]
+    pub fn set_recorder(&mut self, recorder: Option<Recorder>) {
+        self.recorder = recorder;
+    }
+
+    /// Starts collecting traffic statistics. UDP connections also report dropped and late packets
+    pub fn set_stats(&mut self, stats: Option<NetStats>) {
+        if let Link::Udp(transport) = &mut self.link {
+            transport.set_stats(stats.clone());
+        }
+        self.stats = stats;
+    }
+
+    /// Logs in, optionally resuming a previous session. Returns the session id assigned by the server
+    pub async fn handshake(&mut self, credentials: Credentials, session: Option<Uuid>) -> Result<Uuid> {
+        self.send(ClientMessage::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            credentials,
+            session,
+        })
+        .await?;
+
+        match self.receive().await? {
+            ServerMessage::Welcome {
+                protocol_version,
+                session,
+            } => {
+                if protocol_version != PROTOCOL_VERSION {
+                    return Err(Error::ProtocolVersionMismatch {
+                        client: PROTOCOL_VERSION,
+                        server: protocol_version,
+                    }
+                    .into());
+                }
+                Ok(session)
+            }
+            ServerMessage::Rejected { reason } => Err(match reason {
+                RejectionReason::ProtocolVersionMismatch { server_version } => Error::ProtocolVersionMismatch {
+                    client: PROTOCOL_VERSION,
+                    server: server_version,
+                },
+                RejectionReason::InvalidCredentials => Error::InvalidCredentials,
+            }
+            .into()),
+            message => Err(Error::UnexpectedMessage(format!("{:?}", message)).into()),
+        }
+    }
+
+    pub async fn send(&mut self, message: ClientMessage) -> Result<()> {
+        if let Some(recorder) = &self.recorder {
+            recorder.record_outbound(&message);
+        }
+
+        if let (Link::Tcp(_), Some(stats)) = (&self.link, &self.stats) {
+            stats.record_sent(framed_size(&message)?);
+        }
+
+        self.link.send(message).await
+    }
+
+    pub async fn receive(&mut self) -> Result<ServerMessage> {
+        let message = self.link.receive().await?;
+
+        if let Some(recorder) = &self.recorder {
+            recorder.record_inbound(&message);
+        }
+
+        if let (Link::Tcp(_), Some(stats)) = (&self.link, &self.stats) {
+            stats.record_received(framed_size(&message)?);
+        }
+
+        Ok(message)
+    }
+}
+
+/// Size of a message on the wire with the length delimited framing
+fn framed_size<T: Serialize>(message: &T) -> Result<usize> {
+    Ok(bincode::serialized_size(message)? as usize + 4)
+}
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Connection closed by remote host")]
+    ConnectionClosed,
+
+    #[error("Protocol version mismatch: client {client}, server {server}")]
+    ProtocolVersionMismatch { client: u32, server: u32 },
+
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+
+    #[error("Server didn't respond for {0:?}")]
+    Timeout(Duration),
+
+    #[error("Unexpected message: {0}")]
+    UnexpectedMessage(String),
+
+    #[error("Snapshot baseline {0} is missing")]
+    MissingBaseline(u32),
+}
@@ -0,0 +1,139 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use super::clock::ServerClock;
+use super::protocol::EntityId;
+
+/// Remote entity positions, rendered `delay` behind the estimated server time.
+///
+/// Snapshot times are server timestamps in seconds, as returned by `ServerClock::timestamp`
+pub struct Interpolation {
+    delay: f64,
+    max_extrapolation: f64,
+    latest_time: Option<f64>,
+    render_time: f64,
+    entities: HashMap<EntityId, SnapshotBuffer>,
+}
+
+impl Interpolation {
+    pub fn new(delay: Duration, max_extrapolation: Duration) -> Self {
+        Self {
+            delay: delay.as_secs_f64(),
+            max_extrapolation: max_extrapolation.as_secs_f64(),
+            latest_time: None,
+            render_time: 0.0,
+            entities: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, time: f64, entity: EntityId, position: glm::Vec2) {
+        match self.latest_time {
+            Some(latest_time) if latest_time >= time => {}
+            _ => self.latest_time = Some(time),
+        }
+
+        self.entities.entry(entity).or_default().push(time, position);
+    }
+
+    pub fn remove(&mut self, entity: EntityId) {
+        self.entities.remove(&entity);
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(EntityId) -> bool,
+    {
+        self.entities.retain(|entity, _| f(*entity));
+    }
+
+    pub fn update(&mut self, clock: &ServerClock) {
+        let latest_time = match self.latest_time {
+            Some(latest_time) => latest_time,
+            None => return,
+        };
+
+        // Don't run ahead further than entities can be extrapolated
+        self.render_time = (clock.timestamp() - self.delay).min(latest_time + self.max_extrapolation);
+
+        for buffer in self.entities.values_mut() {
+            buffer.prune(self.render_time);
+        }
+    }
+
+    pub fn position(&self, entity: EntityId) -> Option<glm::Vec2> {
+        self.entities
+            .get(&entity)
+            .and_then(|buffer| buffer.sample(self.render_time, self.max_extrapolation))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (EntityId, glm::Vec2)> + '_ {
+        self.entities.iter().filter_map(move |(entity, buffer)| {
+            buffer
+                .sample(self.render_time, self.max_extrapolation)
+                .map(|position| (*entity, position))
+        })
+    }
+}
+
+#[derive(Default)]
+struct SnapshotBuffer {
+    snapshots: VecDeque<(f64, glm::Vec2)>,
+}
+
+impl SnapshotBuffer {
+    fn push(&mut self, time: f64, position: glm::Vec2) {
+        // Snapshots may arrive out of order, keep the buffer sorted by time
+        match self
+            .snapshots
+            .iter()
+            .rposition(|(snapshot_time, _)| *snapshot_time <= time)
+        {
+            Some(index) if self.snapshots[index].0 == time => {}
+            Some(index) => self.snapshots.insert(index + 1, (time, position)),
+            None => self.snapshots.push_front((time, position)),
+        }
+    }
+
+    /// Drops snapshots which are no longer needed to sample `time`
+    fn prune(&mut self, time: f64) {
+        while self.snapshots.len() > 2 && self.snapshots[1].0 <= time {
+            self.snapshots.pop_front();
+        }
+    }
+
+    fn sample(&self, time: f64, max_extrapolation: f64) -> Option<glm::Vec2> {
+        let (first_time, first_position) = self.snapshots.front()?;
+        if time <= *first_time {
+            return Some(*first_position);
+        }
+
+        let next = self
+            .snapshots
+            .iter()
+            .position(|(snapshot_time, _)| *snapshot_time >= time);
+
+        let (from, to) = match next {
+            Some(index) => (&self.snapshots[index - 1], &self.snapshots[index]),
+            None if self.snapshots.len() > 1 => {
+                // The packet is late, extrapolate from the last two snapshots for a limited time
+                let last = self.snapshots.len() - 1;
+                let (from, to) = (&self.snapshots[last - 1], &self.snapshots[last]);
+                let time = time.min(to.0 + max_extrapolation);
+                return Some(lerp(from, to, time));
+            }
+            None => return Some(*first_position),
+        };
+
+        Some(lerp(from, to, time))
+    }
+}
+
+fn lerp((from_time, from): &(f64, glm::Vec2), (to_time, to): &(f64, glm::Vec2), time: f64) -> glm::Vec2 {
+    let span = to_time - from_time;
+    if span <= 0.0 {
+        return *to;
+    }
+
+    let t = ((time - from_time) / span) as f32;
+    from + (to - from) * t
+}
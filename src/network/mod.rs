@@ -0,0 +1,27 @@
+mod backoff;
+mod client;
+mod clock;
+mod connection;
+mod error;
+mod interpolation;
+mod prediction;
+mod protocol;
+mod reliability;
+mod replay;
+mod snapshot;
+mod stats;
+mod udp;
+
+pub use self::backoff::*;
+pub use self::client::*;
+pub use self::clock::*;
+pub use self::connection::*;
+pub use self::error::*;
+pub use self::interpolation::*;
+pub use self::prediction::*;
+pub use self::protocol::*;
+pub use self::reliability::*;
+pub use self::replay::*;
+pub use self::snapshot::*;
+pub use self::stats::*;
+pub use self::udp::*;
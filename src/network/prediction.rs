@@ -0,0 +1,83 @@
+use std::collections::VecDeque;
+
+use super::protocol::InputCommand;
+use crate::collision::CollisionGrid;
+
+/// Locally predicted player position.
+///
+/// Every applied input is kept until the server acknowledges it, so an authoritative
+/// state can be corrected by replaying the inputs the server hasn't processed yet
+pub struct Prediction {
+    position: glm::Vec2,
+    next_sequence: u32,
+    pending: VecDeque<InputCommand>,
+    collision: Option<CollisionGrid>,
+}
+
+impl Prediction {
+    pub fn new(position: glm::Vec2) -> Self {
+        Self {
+            position,
+            next_sequence: 0,
+            pending: VecDeque::new(),
+            collision: None,
+        }
+    }
+
+    /// Movement ignores collisions until the map is known
+    pub fn set_collision(&mut self, collision: CollisionGrid) {
+        self.collision = Some(collision);
+    }
+
+    pub fn apply_input(&mut self, direction: glm::Vec2, dt: f32) -> InputCommand {
+        let command = InputCommand {
+            sequence: self.next_sequence,
+            direction: [direction.x, direction.y],
+            dt,
+        };
+        self.next_sequence += 1;
+
+        self.position = command.apply(&self.position, self.collision.as_ref());
+        self.pending.push_back(command);
+
+        command
+    }
+
+    pub fn reconcile(&mut self, last_input: u32, position: glm::Vec2) {
+        while matches!(self.pending.front(), Some(command) if command.sequence <= last_input) {
+            self.pending.pop_front();
+        }
+
+        self.reset(position);
+    }
+
+    /// Moves to a position set by the server, replaying the inputs it hasn't acknowledged yet
+    pub fn reset(&mut self, position: glm::Vec2) {
+        let collision = self.collision.as_ref();
+        self.position = self
+            .pending
+            .iter()
+            .fold(position, |position, command| command.apply(&position, collision));
+    }
+
+    #[inline]
+    pub fn position(&self) -> &glm::Vec2 {
+        &self.position
+    }
+}
+
+impl InputCommand {
+    pub fn apply(&self, position: &glm::Vec2, collision: Option<&CollisionGrid>) -> glm::Vec2 {
+        let delta = glm::vec2(self.direction[0], self.direction[1]) * self.dt * PLAYER_SPEED;
+        match collision {
+            Some(collision) => {
+                collision.move_and_slide(*position, glm::vec2(PLAYER_HALF_SIZE, PLAYER_HALF_SIZE), delta)
+            }
+            None => position + delta,
+        }
+    }
+}
+
+pub const PLAYER_SPEED: f32 = 10.0;
+/// Half of the player collision box size in tiles
+pub const PLAYER_HALF_SIZE: f32 = 0.3;
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::reliability::{Channel, ChannelMessage};
+use crate::collision::CollisionGrid;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Maximum length of a chat message in characters
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 256;
+
+pub type EntityId = u32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientMessage {
+    Hello {
+        protocol_version: u32,
+        credentials: Credentials,
+        session: Option<Uuid>,
+    },
+    Ping {
+        id: u32,
+        client_time: DateTime<Utc>,
+    },
+    Input(InputCommand),
+    SnapshotAck {
+        sequence: u32,
+    },
+    RequestFullSnapshot,
+    Chat {
+        text: String,
+    },
+}
+
+impl ChannelMessage for ClientMessage {
+    fn channel(&self) -> Channel {
+        match self {
+            // Late pongs and acks are still useful, so they aren't sequenced with snapshots
+            Self::Ping { .. } | Self::SnapshotAck { .. } => Channel::Unreliable,
+            _ => Channel::ReliableOrdered,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    Welcome {
+        protocol_version: u32,
+        session: Uuid,
+    },
+    Rejected {
+        reason: RejectionReason,
+    },
+    Pong {
+        id: u32,
+        client_time: DateTime<Utc>,
+        server_time: DateTime<Utc>,
+    },
+    PlayerState {
+        last_input: u32,
+        position: [f32; 2],
+    },
+    Snapshot(SnapshotDelta),
+    /// Size of the current map in tiles, its blocked tiles and objects, sent before its chunks
+    MapInfo {
+        width: u32,
+        height: u32,
+        collision: CollisionGrid,
+        objects: Vec<MapObject>,
+        /// Tile layers drawn row by row between entities, so they can walk behind them
+        y_sorted_layers: Vec<u32>,
+        /// Where the player is on the map, its spawn point unless a resumed session left it elsewhere
+        player_position: [f32; 2],
+    },
+    ChunkLoaded(ChunkData),
+    ChunkUnloaded {
+        layer: u32,
+        x: i32,
+        y: i32,
+    },
+    Chat {
+        from: String,
+        text: String,
+        time: DateTime<Utc>,
+    },
+}
+
+impl ChannelMessage for ServerMessage {
+    fn channel(&self) -> Channel {
+        match self {
+            Self::Pong { .. } => Channel::Unreliable,
+            Self::PlayerState { .. } | Self::Snapshot(_) => Channel::UnreliableSequenced,
+            _ => Channel::ReliableOrdered,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Credentials {
+    pub login: String,
+    pub password: String,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct InputCommand {
+    pub sequence: u32,
+    pub direction: [f32; 2],
+    pub dt: f32,
+}
+
+/// A square of `CHUNK_SIZE` x `CHUNK_SIZE` tiles of a single map layer.
+///
+/// `x` and `y` are in chunks. Tiles are stored row by row, `0` is an empty tile,
+/// other values are one-based tile indices in the tileset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkData {
+    pub layer: u32,
+    pub x: i32,
+    pub y: i32,
+    pub tiles: Vec<u16>,
+}
+
+/// Object placed on a map object layer in Tiled.
+///
+/// `position` is the center of the object and `size` is zero for points, both are in tiles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    /// Object type set in Tiled, decides which components the entity gets
+    pub kind: String,
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub properties: HashMap<String, PropertyValue>,
+}
+
+/// Custom property value of a Tiled object. Colors and files are strings, object references are ints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityState {
+    pub id: EntityId,
+    pub position: [f32; 2],
+}
+
+/// Entity states encoded against a previously acknowledged snapshot.
+///
+/// Without a `baseline` the delta is a full snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+    pub sequence: u32,
+    pub baseline: Option<u32>,
+    pub time: f64,
+    pub changed: Vec<EntityState>,
+    pub removed: Vec<EntityId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RejectionReason {
+    ProtocolVersionMismatch { server_version: u32 },
+    InvalidCredentials,
+}
@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Channel {
+    /// Resent until acknowledged and delivered in the order of sending
+    ReliableOrdered,
+    /// Never resent, messages older than the latest received one are dropped
+    UnreliableSequenced,
+    /// Never resent, delivered whenever they arrive
+    Unreliable,
+}
+
+pub trait ChannelMessage {
+    fn channel(&self) -> Channel;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Packet {
+    sequence: u32,
+    ack: Option<u32>,
+    ack_bits: u32,
+    messages: Vec<PacketMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PacketMessage {
+    channel: Channel,
+    id: u32,
+    payload: Vec<u8>,
+}
+
+struct Outgoing {
+    payload: Vec<u8>,
+    sent_at: Option<Instant>,
+}
+
+/// Sequencing, acks and channels over unreliable datagrams, independent of the socket.
+///
+/// Every packet acknowledges the latest received packet and 32 packets before it.
+/// Reliable messages are resent until a packet containing them is acknowledged.
+/// Sequences and message ids wrap around when they overflow
+#[derive(Default)]
+pub struct Endpoint {
+    local_sequence: u32,
+    remote_sequence: Option<u32>,
+    remote_bits: u32,
+    ack_pending: bool,
+
+    next_reliable_id: u32,
+    reliable: BTreeMap<u32, Outgoing>,
+    in_flight: VecDeque<(u32, Vec<u32>)>,
+    next_unreliable_id: u32,
+    unreliable: Vec<PacketMessage>,
+
+    expected_reliable_id: u32,
+    received_reliable: BTreeMap<u32, Vec<u8>>,
+    latest_unreliable_id: Option<u32>,
+
+    dropped_packets: u64,
+    late_packets: u64,
+}
+
+impl Endpoint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of packets which left the ack window without being received
+    #[inline]
+    pub fn dropped_packets(&self) -> u64 {
+        self.dropped_packets
+    }
+
+    /// Number of packets received out of order
+    #[inline]
+    pub fn late_packets(&self) -> u64 {
+        self.late_packets
+    }
+
+    /// Queues a message until the next `flush`
+    pub fn push(&mut self, channel: Channel, payload: Vec<u8>) {
+        match channel {
+            Channel::ReliableOrdered => {
+                self.reliable
+                    .insert(self.next_reliable_id, Outgoing { payload, sent_at: None });
+                self.next_reliable_id = self.next_reliable_id.wrapping_add(1);
+            }
+            Channel::UnreliableSequenced => {
+                self.unreliable.push(PacketMessage {
+                    channel,
+                    id: self.next_unreliable_id,
+                    payload,
+                });
+                self.next_unreliable_id = self.next_unreliable_id.wrapping_add(1);
+            }
+            Channel::Unreliable => self.unreliable.push(PacketMessage {
+                channel,
+                id: 0,
+                payload,
+            }),
+        }
+    }
+
+    /// Returns packets with queued messages and reliable messages due for resending.
+    ///
+    /// Messages larger than `MAX_PACKET_PAYLOAD` are sent in a packet of their own
+    pub fn flush(&mut self, now: Instant) -> Result<Vec<Vec<u8>>> {
+        let mut messages = Vec::new();
+
+        for (&id, outgoing) in self.reliable.iter_mut() {
+            if matches!(outgoing.sent_at, Some(sent_at) if now < sent_at + RESEND_DELAY) {
+                continue;
+            }
+            outgoing.sent_at = Some(now);
+
+            messages.push(PacketMessage {
+                channel: Channel::ReliableOrdered,
+                id,
+                payload: outgoing.payload.clone(),
+            });
+        }
+
+        messages.append(&mut self.unreliable);
+
+        let mut packets = Vec::new();
+        let mut batch = Vec::new();
+        let mut batch_size = 0;
+
+        for message in messages {
+            if !batch.is_empty() && batch_size + message.payload.len() > MAX_PACKET_PAYLOAD {
+                packets.push(self.make_packet(std::mem::take(&mut batch))?);
+                batch_size = 0;
+            }
+            batch_size += message.payload.len();
+            batch.push(message);
+        }
+
+        if !batch.is_empty() || (packets.is_empty() && self.ack_pending) {
+            packets.push(self.make_packet(batch)?);
+        }
+
+        Ok(packets)
+    }
+
+    /// Processes a received packet and returns the messages ready for delivery
+    pub fn receive(&mut self, datagram: &[u8]) -> Result<Vec<Vec<u8>>> {
+        let packet: Packet = bincode::deserialize(datagram)?;
+
+        if let Some(ack) = packet.ack {
+            self.acknowledge(ack);
+            for bit in 0..32 {
+                if packet.ack_bits & (1 << bit) != 0 {
+                    self.acknowledge(ack.wrapping_sub(bit + 1));
+                }
+            }
+        }
+
+        if !self.track(packet.sequence) {
+            return Ok(Vec::new());
+        }
+
+        // Packets with acks only are not acknowledged themselves
+        if !packet.messages.is_empty() {
+            self.ack_pending = true;
+        }
+
+        let mut delivered = Vec::new();
+
+        for message in packet.messages {
+            match message.channel {
+                Channel::ReliableOrdered => {
+                    if !is_newer(self.expected_reliable_id, message.id) {
+                        self.received_reliable.entry(message.id).or_insert(message.payload);
+                    }
+                }
+                Channel::UnreliableSequenced => {
+                    if !matches!(self.latest_unreliable_id, Some(latest) if !is_newer(message.id, latest)) {
+                        self.latest_unreliable_id = Some(message.id);
+                        delivered.push(message.payload);
+                    }
+                }
+                Channel::Unreliable => delivered.push(message.payload),
+            }
+        }
+
+        while let Some(payload) = self.received_reliable.remove(&self.expected_reliable_id) {
+            delivered.push(payload);
+            self.expected_reliable_id = self.expected_reliable_id.wrapping_add(1);
+        }
+
+        Ok(delivered)
+    }
+
+    fn make_packet(&mut self, messages: Vec<PacketMessage>) -> Result<Vec<u8>> {
+        let sequence = self.local_sequence;
+        self.local_sequence = self.local_sequence.wrapping_add(1);
+
+        let reliable_ids = messages
+            .iter()
+            .filter(|message| message.channel == Channel::ReliableOrdered)
+            .map(|message| message.id)
+            .collect::<Vec<_>>();
+        if !reliable_ids.is_empty() {
+            self.in_flight.push_back((sequence, reliable_ids));
+            if self.in_flight.len() > MAX_IN_FLIGHT_PACKETS {
+                self.in_flight.pop_front();
+            }
+        }
+
+        self.ack_pending = false;
+
+        Ok(bincode::serialize(&Packet {
+            sequence,
+            ack: self.remote_sequence,
+            ack_bits: self.remote_bits,
+            messages,
+        })?)
+    }
+
+    fn acknowledge(&mut self, sequence: u32) {
+        if let Some(index) = self.in_flight.iter().position(|(packet, _)| *packet == sequence) {
+            if let Some((_, ids)) = self.in_flight.remove(index) {
+                for id in ids {
+                    self.reliable.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Remembers the received packet sequence. Returns false for duplicates and packets too old to be acknowledged
+    fn track(&mut self, sequence: u32) -> bool {
+        match self.remote_sequence {
+            Some(latest) if is_newer(sequence, latest) => {
+                let shift = sequence.wrapping_sub(latest);
+
+                // Sequences leaving the ack window without being received are lost
+                let shifted_out = self.remote_bits >> (32 - shift.min(32));
+                self.dropped_packets +=
+                    (shift.min(32) - shifted_out.count_ones()) as u64 + shift.saturating_sub(33) as u64;
+
+                self.remote_bits =
+                    self.remote_bits.checked_shl(shift).unwrap_or(0) | 1u32.checked_shl(shift - 1).unwrap_or(0);
+                self.remote_sequence = Some(sequence);
+                true
+            }
+            Some(latest) => {
+                let distance = latest.wrapping_sub(sequence);
+                if distance == 0 || distance > 32 {
+                    return false;
+                }
+
+                let bit = 1 << (distance - 1);
+                if self.remote_bits & bit != 0 {
+                    return false;
+                }
+                self.remote_bits |= bit;
+                self.late_packets += 1;
+                true
+            }
+            None => {
+                // Nothing was sent before the first sequence
+                self.remote_bits = u32::MAX.checked_shl(sequence).unwrap_or(0);
+                self.dropped_packets += sequence.saturating_sub(32) as u64;
+                self.remote_sequence = Some(sequence);
+                true
+            }
+        }
+    }
+}
+
+/// Whether sequence `a` comes after `b`. Sequences wrap around, so the one less than half of the range ahead is newer
+#[inline]
+fn is_newer(a: u32, b: u32) -> bool {
+    (a.wrapping_sub(b) as i32) > 0
+}
+
+pub const RESEND_DELAY: Duration = Duration::from_millis(100);
+const MAX_PACKET_PAYLOAD: usize = 1200;
+const MAX_IN_FLIGHT_PACKETS: usize = 1024;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequences_wrap_around() {
+        let start = u32::MAX - 2;
+        let mut client = Endpoint {
+            local_sequence: start,
+            next_reliable_id: start,
+            next_unreliable_id: start,
+            ..Endpoint::new()
+        };
+        let mut server = Endpoint {
+            remote_sequence: Some(start - 1),
+            remote_bits: u32::MAX,
+            expected_reliable_id: start,
+            latest_unreliable_id: Some(start - 1),
+            ..Endpoint::new()
+        };
+        let now = Instant::now();
+
+        let mut delivered = Vec::new();
+        for i in 0..6u8 {
+            client.push(Channel::ReliableOrdered, vec![i]);
+            client.push(Channel::UnreliableSequenced, vec![100 + i]);
+            for packet in client.flush(now).unwrap() {
+                delivered.extend(server.receive(&packet).unwrap());
+            }
+            for packet in server.flush(now).unwrap() {
+                assert!(client.receive(&packet).unwrap().is_empty());
+            }
+        }
+
+        let expected = (0..6u8).flat_map(|i| vec![vec![100 + i], vec![i]]).collect::<Vec<_>>();
+        assert_eq!(delivered, expected);
+        assert_eq!(server.dropped_packets(), 0);
+
+        // Every reliable message was acknowledged across the wrap
+        assert!(client.reliable.is_empty());
+        assert!(client.flush(now + RESEND_DELAY).unwrap().is_empty());
+
+        assert!(is_newer(1, u32::MAX));
+        assert!(!is_newer(u32::MAX, 1));
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::fs::File;
+use tokio::sync::mpsc;
+use tokio_serde::formats::Bincode;
+use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
+
+use super::protocol::{ClientMessage, ServerMessage};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ReplayEntry {
+    Inbound {
+        time: DateTime<Utc>,
+        message: ServerMessage,
+    },
+    Outbound {
+        time: DateTime<Utc>,
+        message: ClientMessage,
+    },
+}
+
+/// Writes every recorded message to a replay file in the background.
+///
+/// Cheap to clone, all clones write into the same file
+#[derive(Clone)]
+pub struct Recorder {
+    entries: mpsc::UnboundedSender<ReplayEntry>,
+}
+
+impl Recorder {
+    pub async fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).await?;
+        let mut writer: ReplayWriter =
+            tokio_serde::Framed::new(FramedWrite::new(file, LengthDelimitedCodec::new()), Bincode::default());
+
+        let (entries_tx, mut entries_rx) = mpsc::unbounded_channel();
+
+        let path = path.to_owned();
+        tokio::spawn(async move {
+            while let Some(entry) = entries_rx.recv().await {
+                if let Err(e) = writer.send(entry).await {
+                    log::error!("Failed to write replay entry into {}: {:?}", path.display(), e);
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { entries: entries_tx })
+    }
+
+    pub fn record_inbound(&self, message: &ServerMessage) {
+        let _ = self.entries.send(ReplayEntry::Inbound {
+            time: Utc::now(),
+            message: message.clone(),
+        });
+    }
+
+    pub fn record_outbound(&self, message: &ClientMessage) {
+        let _ = self.entries.send(ReplayEntry::Outbound {
+            time: Utc::now(),
+            message: message.clone(),
+        });
+    }
+}
+
+pub struct Replay {
+    reader: ReplayReader,
+}
+
+impl Replay {
+    pub async fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).await?;
+
+        Ok(Self {
+            reader: tokio_serde::Framed::new(FramedRead::new(file, LengthDelimitedCodec::new()), Bincode::default()),
+        })
+    }
+
+    pub async fn next(&mut self) -> Result<Option<ReplayEntry>> {
+        Ok(self.reader.next().await.transpose()?)
+    }
+}
+
+type ReplayWriter = tokio_serde::Framed<
+    FramedWrite<File, LengthDelimitedCodec>,
+    ReplayEntry,
+    ReplayEntry,
+    Bincode<ReplayEntry, ReplayEntry>,
+>;
+
+type ReplayReader = tokio_serde::Framed<
+    FramedRead<File, LengthDelimitedCodec>,
+    ReplayEntry,
+    ReplayEntry,
+    Bincode<ReplayEntry, ReplayEntry>,
+>;
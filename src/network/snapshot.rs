@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use super::error::Error;
+use super::protocol::{EntityId, EntityState, SnapshotDelta};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub sequence: u32,
+    pub time: f64,
+    pub entities: BTreeMap<EntityId, EntityState>,
+}
+
+impl Snapshot {
+    pub fn new(sequence: u32, time: f64, entities: impl IntoIterator<Item = EntityState>) -> Self {
+        Self {
+            sequence,
+            time,
+            entities: entities.into_iter().map(|entity| (entity.id, entity)).collect(),
+        }
+    }
+
+    /// Encodes only entities which differ from `baseline`, or all of them when there is no baseline
+    pub fn delta_from(&self, baseline: Option<&Snapshot>) -> SnapshotDelta {
+        let (changed, removed) = match baseline {
+            Some(baseline) => (
+                self.entities
+                    .values()
+                    .filter(|entity| baseline.entities.get(&entity.id) != Some(entity))
+                    .copied()
+                    .collect(),
+                baseline
+                    .entities
+                    .keys()
+                    .filter(|id| !self.entities.contains_key(id))
+                    .copied()
+                    .collect(),
+            ),
+            None => (self.entities.values().copied().collect(), Vec::new()),
+        };
+
+        SnapshotDelta {
+            sequence: self.sequence,
+            baseline: baseline.map(|baseline| baseline.sequence),
+            time: self.time,
+            changed,
+            removed,
+        }
+    }
+
+    pub fn from_delta(delta: SnapshotDelta, baseline: Option<&Snapshot>) -> Self {
+        let mut entities = baseline.map(|baseline| baseline.entities.clone()).unwrap_or_default();
+
+        for id in delta.removed {
+            entities.remove(&id);
+        }
+        entities.extend(delta.changed.into_iter().map(|entity| (entity.id, entity)));
+
+        Self {
+            sequence: delta.sequence,
+            time: delta.time,
+            entities,
+        }
+    }
+}
+
+/// Server side of the snapshot exchange. Encodes snapshots against the latest acknowledged one
+#[derive(Default)]
+pub struct SnapshotSender {
+    next_sequence: u32,
+    acknowledged: Option<u32>,
+    sent: VecDeque<Snapshot>,
+}
+
+impl SnapshotSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn encode(&mut self, time: f64, entities: impl IntoIterator<Item = EntityState>) -> SnapshotDelta {
+        let snapshot = Snapshot::new(self.next_sequence, time, entities);
+        self.next_sequence = self.next_sequence.wrapping_add(1);
+
+        let baseline = self
+            .acknowledged
+            .and_then(|sequence| self.sent.iter().find(|snapshot| snapshot.sequence == sequence));
+        let delta = snapshot.delta_from(baseline);
+
+        self.sent.push_back(snapshot);
+        if self.sent.len() > SNAPSHOT_HISTORY_SIZE {
+            self.sent.pop_front();
+        }
+
+        delta
+    }
+
+    pub fn acknowledge(&mut self, sequence: u32) {
+        if !self.sent.iter().any(|snapshot| snapshot.sequence == sequence) {
+            return;
+        }
+
+        match self.acknowledged {
+            Some(acknowledged) if acknowledged >= sequence => {}
+            _ => self.acknowledged = Some(sequence),
+        }
+
+        while matches!(self.sent.front(), Some(snapshot) if snapshot.sequence < sequence) {
+            self.sent.pop_front();
+        }
+    }
+
+    /// Forgets the acknowledged baseline, so the next snapshot is sent in full
+    #[inline]
+    pub fn reset(&mut self) {
+        self.acknowledged = None;
+    }
+}
+
+/// Client side of the snapshot exchange. Decodes deltas against previously received snapshots
+#[derive(Default)]
+pub struct SnapshotReceiver {
+    received: VecDeque<Snapshot>,
+    full_snapshot_requested: bool,
+}
+
+impl SnapshotReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the decoded snapshot, `None` for stale deltas or `Error::MissingBaseline`
+    /// when a full snapshot has to be requested
+    pub fn receive(&mut self, delta: SnapshotDelta) -> Result<Option<&Snapshot>, Error> {
+        if matches!(self.received.back(), Some(latest) if latest.sequence >= delta.sequence) {
+            return Ok(None);
+        }
+
+        let snapshot = match delta.baseline {
+            Some(sequence) => {
+                let baseline = self
+                    .received
+                    .iter()
+                    .find(|snapshot| snapshot.sequence == sequence)
+                    .ok_or(Error::MissingBaseline(sequence))?;
+                Snapshot::from_delta(delta, Some(baseline))
+            }
+            None => {
+                self.full_snapshot_requested = false;
+                Snapshot::from_delta(delta, None)
+            }
+        };
+
+        self.received.push_back(snapshot);
+        if self.received.len() > SNAPSHOT_HISTORY_SIZE {
+            self.received.pop_front();
+        }
+
+        Ok(self.received.back())
+    }
+
+    /// Returns whether a full snapshot has to be requested after `Error::MissingBaseline`.
+    ///
+    /// Only the first call does until the full snapshot arrives, so a burst of lost packets
+    /// doesn't send a request for every delta that follows it
+    pub fn request_full_snapshot(&mut self) -> bool {
+        !std::mem::replace(&mut self.full_snapshot_requested, true)
+    }
+
+    #[inline]
+    pub fn reset(&mut self) {
+        self.received.clear();
+        self.full_snapshot_requested = false;
+    }
+}
+
+const SNAPSHOT_HISTORY_SIZE: usize = 32;
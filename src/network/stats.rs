@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Rolling connection statistics.
+///
+/// Cheap to clone, all clones share the same counters
+#[derive(Clone, Default)]
+pub struct NetStats {
+    state: Arc<Mutex<StatsState>>,
+}
+
+#[derive(Default)]
+struct StatsState {
+    rtt_samples: VecDeque<Duration>,
+    jitter: Option<Duration>,
+    traffic: VecDeque<TrafficSample>,
+    dropped_packets: u64,
+    late_packets: u64,
+}
+
+struct TrafficSample {
+    time: Instant,
+    sent: usize,
+    received: usize,
+}
+
+impl NetStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_rtt(&self, rtt: Duration) {
+        let mut state = self.state.lock().unwrap();
+
+        // Running estimate of RFC 3550 with its 1/16 gain, fed with differences between
+        // consecutive round trips, since one way transit times can't be measured without synced clocks
+        if let Some(previous) = state.rtt_samples.back() {
+            let difference = (rtt.as_secs_f64() - previous.as_secs_f64()).abs();
+            let jitter = state.jitter.unwrap_or_default().as_secs_f64();
+            state.jitter = Some(Duration::from_secs_f64(jitter + (difference - jitter) / 16.0));
+        }
+
+        state.rtt_samples.push_back(rtt);
+        if state.rtt_samples.len() > RTT_SAMPLE_COUNT {
+            state.rtt_samples.pop_front();
+        }
+    }
+
+    #[inline]
+    pub fn record_sent(&self, bytes: usize) {
+        self.record_traffic(bytes, 0);
+    }
+
+    #[inline]
+    pub fn record_received(&self, bytes: usize) {
+        self.record_traffic(0, bytes);
+    }
+
+    pub fn record_dropped(&self, count: u64) {
+        self.state.lock().unwrap().dropped_packets += count;
+    }
+
+    pub fn record_late(&self, count: u64) {
+        self.state.lock().unwrap().late_packets += count;
+    }
+
+    pub fn report(&self) -> NetStatsReport {
+        let mut state = self.state.lock().unwrap();
+        state.prune(Instant::now());
+
+        let rtt = match state.rtt_samples.len() {
+            0 => None,
+            count => Some(state.rtt_samples.iter().sum::<Duration>() / count as u32),
+        };
+
+        let window = TRAFFIC_WINDOW.as_secs_f64();
+        NetStatsReport {
+            rtt,
+            jitter: state.jitter,
+            bytes_out_per_second: state.traffic.iter().map(|sample| sample.sent).sum::<usize>() as f64 / window,
+            bytes_in_per_second: state.traffic.iter().map(|sample| sample.received).sum::<usize>() as f64 / window,
+            dropped_packets: state.dropped_packets,
+            late_packets: state.late_packets,
+        }
+    }
+
+    fn record_traffic(&self, sent: usize, received: usize) {
+        let now = Instant::now();
+
+        let mut state = self.state.lock().unwrap();
+        state.traffic.push_back(TrafficSample {
+            time: now,
+            sent,
+            received,
+        });
+        state.prune(now);
+    }
+}
+
+impl StatsState {
+    fn prune(&mut self, now: Instant) {
+        while matches!(self.traffic.front(), Some(sample) if now.duration_since(sample.time) > TRAFFIC_WINDOW) {
+            self.traffic.pop_front();
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct NetStatsReport {
+    pub rtt: Option<Duration>,
+    pub jitter: Option<Duration>,
+    pub bytes_in_per_second: f64,
+    pub bytes_out_per_second: f64,
+    pub dropped_packets: u64,
+    pub late_packets: u64,
+}
+
+impl fmt::Display for NetStatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rtt {}, jitter {}, in {:.1} KB/s, out {:.1} KB/s, dropped {}, late {}",
+            Millis(self.rtt),
+            Millis(self.jitter),
+            self.bytes_in_per_second / 1024.0,
+            self.bytes_out_per_second / 1024.0,
+            self.dropped_packets,
+            self.late_packets
+        )
+    }
+}
+
+/// Duration in milliseconds, or a dash while it is unknown
+#[derive(Debug, Copy, Clone)]
+pub struct Millis(pub Option<Duration>);
+
+impl fmt::Display for Millis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(duration) => write!(f, "{:.1} ms", duration.as_secs_f64() * 1000.0),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+const RTT_SAMPLE_COUNT: usize = 16;
+const TRAFFIC_WINDOW: Duration = Duration::from_secs(2);
@@ -0,0 +1,280 @@
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+use super::error::Error;
+use super::reliability::{ChannelMessage, Endpoint};
+use super::stats::NetStats;
+
+/// Message transport over UDP, either a socket connected to the server
+/// or a peer accepted by `UdpListener`
+pub struct UdpTransport<I, O> {
+    datagrams: Datagrams,
+    endpoint: Endpoint,
+    received: VecDeque<I>,
+    flush_interval: tokio::time::Interval,
+    last_received: Instant,
+    timeout: Duration,
+    stats: Option<NetStats>,
+    _marker: PhantomData<O>,
+}
+
+impl<I, O> UdpTransport<I, O>
+where
+    I: DeserializeOwned,
+    O: Serialize + ChannelMessage,
+{
+    /// Socket connected to `address`, the connection is lost after `timeout` without any packets
+    pub async fn connect(address: SocketAddr, timeout: Duration) -> Result<Self> {
+        let local_address: SocketAddr = if address.is_ipv4() {
+            "0.0.0.0:0".parse()?
+        } else {
+            "[::]:0".parse()?
+        };
+
+        let socket = UdpSocket::bind(local_address).await?;
+        socket.connect(address).await?;
+
+        Ok(Self::new(
+            Datagrams::Socket {
+                socket,
+                buffer: vec![0; MAX_DATAGRAM_SIZE],
+            },
+            timeout,
+        ))
+    }
+
+    fn new(datagrams: Datagrams, timeout: Duration) -> Self {
+        Self {
+            datagrams,
+            endpoint: Endpoint::new(),
+            received: VecDeque::new(),
+            flush_interval: tokio::time::interval(FLUSH_INTERVAL),
+            last_received: Instant::now(),
+            timeout,
+            stats: None,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn set_stats(&mut self, stats: Option<NetStats>) {
+        self.stats = stats;
+    }
+
+    pub async fn send(&mut self, message: O) -> Result<()> {
+        self.endpoint.push(message.channel(), bincode::serialize(&message)?);
+        self.flush().await
+    }
+
+    /// Waits for the next message, resending unacknowledged ones meanwhile.
+    ///
+    /// Fails with `Error::Timeout` when nothing was received for the timeout of the transport
+    pub async fn receive(&mut self) -> Result<I> {
+        loop {
+            if let Some(message) = self.received.pop_front() {
+                return Ok(message);
+            }
+
+            tokio::select! {
+                datagram = self.datagrams.recv() => {
+                    let datagram = datagram?;
+                    self.last_received = Instant::now();
+
+                    let (dropped, late) = (self.endpoint.dropped_packets(), self.endpoint.late_packets());
+                    let payloads = match self.endpoint.receive(&datagram) {
+                        Ok(payloads) => payloads,
+                        Err(e) => {
+                            log::warn!("Dropped malformed packet: {}", e);
+                            continue;
+                        }
+                    };
+
+                    if let Some(stats) = &self.stats {
+                        stats.record_received(datagram.len());
+                        stats.record_dropped(self.endpoint.dropped_packets() - dropped);
+                        stats.record_late(self.endpoint.late_packets() - late);
+                    }
+                    for payload in payloads {
+                        self.received.push_back(bincode::deserialize(&payload)?);
+                    }
+                }
+                _ = self.flush_interval.tick() => {
+                    if self.last_received.elapsed() > self.timeout {
+                        return Err(Error::Timeout(self.timeout).into());
+                    }
+                    self.flush().await?;
+                }
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        for packet in self.endpoint.flush(Instant::now())? {
+            if let Some(stats) = &self.stats {
+                stats.record_sent(packet.len());
+            }
+            self.datagrams.send(packet).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Accepts UDP peers on a single socket, routing datagrams by the sender address
+pub struct UdpListener {
+    local_address: SocketAddr,
+    accepted: mpsc::UnboundedReceiver<(Datagrams, SocketAddr)>,
+    timeout: Duration,
+}
+
+impl UdpListener {
+    /// Accepted peers are lost after `timeout` without any packets
+    pub async fn bind(address: SocketAddr, timeout: Duration) -> Result<Self> {
+        let socket = UdpSocket::bind(address).await?;
+        let local_address = socket.local_addr()?;
+
+        let (accepted_tx, accepted_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            if let Err(e) = route_datagrams(socket, accepted_tx).await {
+                log::error!("UDP listener on {} failed: {:?}", local_address, e);
+            }
+        });
+
+        Ok(Self {
+            local_address,
+            accepted: accepted_rx,
+            timeout,
+        })
+    }
+
+    #[inline]
+    pub fn local_address(&self) -> SocketAddr {
+        self.local_address
+    }
+
+    pub async fn accept<I, O>(&mut self) -> Result<(UdpTransport<I, O>, SocketAddr)>
+    where
+        I: DeserializeOwned,
+        O: Serialize + ChannelMessage,
+    {
+        let (datagrams, address) = self.accepted.recv().await.ok_or(Error::ConnectionClosed)?;
+        Ok((UdpTransport::new(datagrams, self.timeout), address))
+    }
+}
+
+async fn route_datagrams(socket: UdpSocket, accepted: mpsc::UnboundedSender<(Datagrams, SocketAddr)>) -> Result<()> {
+    let (mut socket_rx, mut socket_tx) = socket.split();
+    let (outbound_tx, mut outbound_rx) = mpsc::unbounded_channel::<(SocketAddr, Vec<u8>)>();
+    let (closed_tx, mut closed_rx) = mpsc::unbounded_channel::<(SocketAddr, u64)>();
+
+    // Ids tell a new peer apart from the closed one at the same address
+    let mut peers = HashMap::<SocketAddr, (u64, mpsc::UnboundedSender<Vec<u8>>)>::new();
+    let mut next_peer_id = 0;
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+
+    loop {
+        tokio::select! {
+            received = socket_rx.recv_from(&mut buffer) => {
+                let (len, address) = received?;
+                let datagram = buffer[..len].to_vec();
+
+                let datagram = match peers.get(&address) {
+                    Some((_, peer)) => match peer.send(datagram) {
+                        Ok(()) => continue,
+                        // The peer transport was dropped, the datagram starts a new one
+                        Err(mpsc::error::SendError(datagram)) => datagram,
+                    },
+                    None => datagram,
+                };
+
+                let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+                let _ = inbound_tx.send(datagram);
+
+                let id = next_peer_id;
+                next_peer_id += 1;
+                peers.insert(address, (id, inbound_tx));
+
+                let datagrams = Datagrams::Peer {
+                    address,
+                    id,
+                    inbound: inbound_rx,
+                    outbound: outbound_tx.clone(),
+                    closed: closed_tx.clone(),
+                };
+                if accepted.send((datagrams, address)).is_err() {
+                    return Ok(());
+                }
+            }
+            Some((address, datagram)) = outbound_rx.recv() => {
+                socket_tx.send_to(&datagram, &address).await?;
+            }
+            Some((address, id)) = closed_rx.recv() => {
+                if matches!(peers.get(&address), Some((peer_id, _)) if *peer_id == id) {
+                    peers.remove(&address);
+                }
+            }
+        }
+    }
+}
+
+enum Datagrams {
+    Socket {
+        socket: UdpSocket,
+        buffer: Vec<u8>,
+    },
+    Peer {
+        address: SocketAddr,
+        id: u64,
+        inbound: mpsc::UnboundedReceiver<Vec<u8>>,
+        outbound: mpsc::UnboundedSender<(SocketAddr, Vec<u8>)>,
+        /// Tells the listener to forget the peer when its transport is dropped
+        closed: mpsc::UnboundedSender<(SocketAddr, u64)>,
+    },
+}
+
+impl Datagrams {
+    async fn recv(&mut self) -> Result<Vec<u8>> {
+        match self {
+            Self::Socket { socket, buffer } => {
+                let len = socket.recv(buffer).await?;
+                Ok(buffer[..len].to_vec())
+            }
+            Self::Peer { inbound, .. } => Ok(inbound.recv().await.ok_or(Error::ConnectionClosed)?),
+        }
+    }
+
+    async fn send(&mut self, datagram: Vec<u8>) -> Result<()> {
+        match self {
+            Self::Socket { socket, .. } => {
+                socket.send(&datagram).await?;
+            }
+            Self::Peer { address, outbound, .. } => {
+                outbound
+                    .send((*address, datagram))
+                    .map_err(|_| Error::ConnectionClosed)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Datagrams {
+    fn drop(&mut self) {
+        if let Self::Peer {
+            address, id, closed, ..
+        } = self
+        {
+            let _ = closed.send((*address, *id));
+        }
+    }
+}
+
+const FLUSH_INTERVAL: Duration = Duration::from_millis(20);
+const MAX_DATAGRAM_SIZE: usize = 65_507;
@@ -0,0 +1,82 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::collision::CollisionGrid;
+
+/// Finds the shortest path between two tiles with A*, moving in eight directions.
+///
+/// Diagonal steps can't cut corners, both tiles next to them must be free too.
+/// The path starts with the tile after `start` and ends with `goal`, it is empty when they are the same
+pub fn find_path(grid: &CollisionGrid, start: (i32, i32), goal: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+    if grid.is_blocked(goal.0, goal.1) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from = HashMap::new();
+    let mut costs = HashMap::new();
+
+    open.push(Reverse((heuristic(start, goal), 0, start)));
+    costs.insert(start, 0);
+
+    while let Some(Reverse((_, cost, tile))) = open.pop() {
+        if tile == goal {
+            return Some(reconstruct_path(&came_from, start, goal));
+        }
+
+        // Skips entries superseded by a cheaper path
+        if matches!(costs.get(&tile), Some(best) if cost > *best) {
+            continue;
+        }
+
+        for (dx, dy) in NEIGHBOURS.iter() {
+            let next = (tile.0 + dx, tile.1 + dy);
+            if grid.is_blocked(next.0, next.1) {
+                continue;
+            }
+
+            let diagonal = *dx != 0 && *dy != 0;
+            if diagonal && (grid.is_blocked(tile.0 + dx, tile.1) || grid.is_blocked(tile.0, tile.1 + dy)) {
+                continue;
+            }
+
+            let next_cost = cost + if diagonal { DIAGONAL_COST } else { ORTHOGONAL_COST };
+            if !matches!(costs.get(&next), Some(best) if next_cost >= *best) {
+                costs.insert(next, next_cost);
+                came_from.insert(next, tile);
+                open.push(Reverse((next_cost + heuristic(next, goal), next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+fn reconstruct_path(
+    came_from: &HashMap<(i32, i32), (i32, i32)>,
+    start: (i32, i32),
+    goal: (i32, i32),
+) -> Vec<(i32, i32)> {
+    let mut path = Vec::new();
+    let mut tile = goal;
+    while tile != start {
+        path.push(tile);
+        tile = came_from[&tile];
+    }
+    path.reverse();
+    path
+}
+
+/// Octile distance, the cost of the path if nothing was blocked
+fn heuristic(from: (i32, i32), to: (i32, i32)) -> i32 {
+    let dx = (from.0 - to.0).abs();
+    let dy = (from.1 - to.1).abs();
+    let (short, long) = if dx < dy { (dx, dy) } else { (dy, dx) };
+    short * DIAGONAL_COST + (long - short) * ORTHOGONAL_COST
+}
+
+const NEIGHBOURS: [(i32, i32); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// Costs of a step scaled by 10, so diagonals are close to the square root of 2
+const ORTHOGONAL_COST: i32 = 10;
+const DIAGONAL_COST: i32 = 14;
@@ -0,0 +1,87 @@
+use once_cell::sync::OnceCell;
+use winit::dpi::PhysicalSize;
+
+pub struct Camera {
+    pub view: glm::Mat4,
+    pub projection: glm::Mat4,
+    position: glm::Vec2,
+    size: PhysicalSize<u32>,
+    scale: u32,
+}
+
+impl Camera {
+    pub fn new(size: PhysicalSize<u32>) -> Self {
+        let mut camera = Self {
+            view: glm::identity(),
+            projection: glm::identity(),
+            position: glm::vec2(0.0, 0.0),
+            size,
+            scale: 2,
+        };
+        camera.update_projection(size);
+        camera
+    }
+
+    #[inline]
+    pub fn set_view(&mut self, view: &glm::Mat4) {
+        self.view.copy_from(view);
+    }
+
+    #[inline]
+    pub fn look_at(&mut self, position: &glm::Vec2) {
+        self.position = *position;
+        self.set_view(
+            &(glm::scaling(&glm::vec3(TILE_SIZE, TILE_SIZE, 1.0))
+                * glm::translation(&glm::vec3(-position.x, -position.y, 0.0))),
+        );
+    }
+
+    /// Center of the screen in tiles
+    #[inline]
+    pub fn position(&self) -> &glm::Vec2 {
+        &self.position
+    }
+
+    /// Converts a point in window pixels to tiles
+    #[inline]
+    pub fn screen_to_world(&self, point: &glm::Vec2) -> glm::Vec2 {
+        let center = glm::vec2(self.size.width as f32, self.size.height as f32) / 2.0;
+        self.position + (point - center) / (self.scale as f32 * TILE_SIZE)
+    }
+
+    /// Half of the visible area in tiles
+    #[inline]
+    pub fn half_extent(&self) -> glm::Vec2 {
+        glm::vec2(self.size.width as f32, self.size.height as f32) / (2.0 * self.scale as f32 * TILE_SIZE)
+    }
+
+    #[inline]
+    pub fn update_projection(&mut self, size: PhysicalSize<u32>) {
+        self.size = size;
+        let (width, height) = (size.width, size.height);
+        let factor = 2.0 * self.scale as f32;
+
+        #[cfg_attr(rustfmt, rustfmt_skip)]
+        let correction_matrix = OPENGL_TO_WGPU_MATRIX.get_or_init(|| glm::mat4(
+            1.0, 0.0, 0.0, 0.0,
+            0.0, -1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ));
+
+        self.projection = correction_matrix
+            * glm::ortho(
+                -(width as f32 / factor),
+                width as f32 / factor,
+                -(height as f32 / factor),
+                height as f32 / factor,
+                -10.0,
+                10.0,
+            );
+    }
+}
+
+/// Size of a tile in pixels before scaling
+const TILE_SIZE: f32 = 32.0;
+
+static OPENGL_TO_WGPU_MATRIX: OnceCell<glm::Mat4> = OnceCell::new();
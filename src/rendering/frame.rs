@@ -1,4 +1,4 @@
-use super::{RenderingState, TileMapRenderer};
+use super::{RenderingState, SpriteRenderer, TextRenderer, TileMapRenderer};
 
 pub struct Frame<'s> {
     rendering_state: &'s mut RenderingState,
@@ -18,6 +18,7 @@ impl<'s> Frame<'s> {
     pub fn next_pass<'r>(&'r mut self) -> Option<Pass<'r, 's>> {
         match self.state.increment() {
             FrameState::Draw => Some(Pass::World(DrawPass { frame: self })),
+            FrameState::Ui => Some(Pass::Ui(UiPass { frame: self })),
             _ => None,
         }
     }
@@ -30,6 +31,7 @@ impl<'s> Frame<'s> {
 #[derive(Copy, Clone)]
 enum FrameState {
     Draw,
+    Ui,
     Compose,
     Submit,
     End,
@@ -40,7 +42,8 @@ impl FrameState {
         std::mem::replace(
             self,
             match self {
-                FrameState::Draw => FrameState::Submit, // FrameState::Compose,
+                FrameState::Draw => FrameState::Ui,
+                FrameState::Ui => FrameState::Submit, // FrameState::Compose,
                 // FrameState::Compose => FrameState::Submit,
                 _ => FrameState::End,
             },
@@ -50,6 +53,7 @@ impl FrameState {
 
 pub enum Pass<'r, 's> {
     World(DrawPass<'r, 's>),
+    Ui(UiPass<'r, 's>),
 }
 
 pub struct DrawPass<'r, 's> {
@@ -74,4 +78,33 @@ impl<'r, 's> DrawPass<'r, 's> {
     pub fn tile_map_renderer(&self) -> &TileMapRenderer {
         &self.frame.rendering_state.tilemap_renderer
     }
+
+    pub fn sprite_renderer(&self) -> &SpriteRenderer {
+        &self.frame.rendering_state.sprite_renderer
+    }
+}
+
+/// Screen space pass drawn over the world
+pub struct UiPass<'r, 's> {
+    frame: &'r mut Frame<'s>,
+}
+
+impl<'r, 's> UiPass<'r, 's> {
+    pub fn start<'a>(&'a self, encoder: &'a mut wgpu::CommandEncoder) -> wgpu::RenderPass<'a> {
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: &self.frame.frame_output.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        })
+    }
+
+    pub fn text_renderer(&self) -> &TextRenderer {
+        &self.frame.rendering_state.text_renderer
+    }
 }
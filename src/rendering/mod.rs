@@ -1,10 +1,18 @@
+mod camera;
 mod error;
 mod frame;
 mod rendering_state;
+mod sprite_renderer;
+mod text_renderer;
+mod tile_layers;
 mod tilemap_renderer;
 pub mod utils;
 
+pub use self::camera::*;
 pub use self::error::*;
 pub use self::frame::*;
 pub use self::rendering_state::*;
+pub use self::sprite_renderer::*;
+pub use self::text_renderer::*;
+pub use self::tile_layers::*;
 pub use self::tilemap_renderer::*;
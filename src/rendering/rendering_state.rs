@@ -6,7 +6,7 @@ use winit::window::Window;
 
 use super::error::Error;
 use super::frame::Frame;
-use crate::rendering::TileMapRenderer;
+use crate::rendering::{SpriteRenderer, TextRenderer, TileMapRenderer};
 
 pub struct RenderingState {
     surface: wgpu::Surface,
@@ -16,6 +16,8 @@ pub struct RenderingState {
     swap_chain: wgpu::SwapChain,
 
     pub(super) tilemap_renderer: TileMapRenderer,
+    pub(super) sprite_renderer: SpriteRenderer,
+    pub(super) text_renderer: TextRenderer,
 }
 
 impl RenderingState {
@@ -56,6 +58,8 @@ impl RenderingState {
         let swap_chain = device.create_swap_chain(&surface, &swap_chain_descriptor);
 
         let tilemap_renderer = TileMapRenderer::new(&device, &queue);
+        let sprite_renderer = SpriteRenderer::new(&device, &queue);
+        let text_renderer = TextRenderer::new(&device, &queue, window_size);
 
         Ok(Self {
             surface,
@@ -64,12 +68,15 @@ impl RenderingState {
             swap_chain_descriptor,
             swap_chain,
             tilemap_renderer,
+            sprite_renderer,
+            text_renderer,
         })
     }
 
     pub fn handle_resize(&mut self, size: PhysicalSize<u32>) {
         self.swap_chain_descriptor.width = size.width;
         self.swap_chain_descriptor.height = size.height;
+        self.text_renderer.update_screen_size(&self.device, size);
     }
 
     pub fn frame(&mut self) -> (wgpu::CommandEncoder, Frame) {
@@ -112,6 +119,16 @@ impl RenderingState {
     pub fn tilemap_renderer(&mut self) -> &mut TileMapRenderer {
         &mut self.tilemap_renderer
     }
+
+    #[inline]
+    pub fn sprite_renderer(&mut self) -> &mut SpriteRenderer {
+        &mut self.sprite_renderer
+    }
+
+    #[inline]
+    pub fn text_renderer(&mut self) -> &mut TextRenderer {
+        &mut self.text_renderer
+    }
 }
 
 pub const SWAPCHAIN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
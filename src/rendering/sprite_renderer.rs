@@ -0,0 +1,285 @@
+use std::ops::Range;
+
+use super::utils;
+use super::SWAPCHAIN_FORMAT;
+
+/// Draws one tile sized quads from the sprite atlas, centered at their positions in tiles
+pub struct SpriteRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    camera_bind_group_layout: wgpu::BindGroupLayout,
+    camera_bind_group: wgpu::BindGroup,
+    atlas_bind_group_layout: wgpu::BindGroupLayout,
+    atlas_bind_group: wgpu::BindGroup,
+}
+
+impl SpriteRenderer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            bindings: &[wgpu::BindGroupLayoutEntry::new(
+                0,
+                wgpu::ShaderStage::VERTEX,
+                wgpu::BindingType::UniformBuffer {
+                    dynamic: false,
+                    min_binding_size: None,
+                },
+            )],
+        });
+
+        let atlas_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            bindings: &[
+                wgpu::BindGroupLayoutEntry::new(
+                    0,
+                    wgpu::ShaderStage::FRAGMENT,
+                    wgpu::BindingType::SampledTexture {
+                        dimension: wgpu::TextureViewDimension::D2,
+                        component_type: wgpu::TextureComponentType::Float,
+                        multisampled: false,
+                    },
+                ),
+                wgpu::BindGroupLayoutEntry::new(
+                    1,
+                    wgpu::ShaderStage::FRAGMENT,
+                    wgpu::BindingType::Sampler { comparison: false },
+                ),
+                wgpu::BindGroupLayoutEntry::new(
+                    2,
+                    wgpu::ShaderStage::FRAGMENT,
+                    wgpu::BindingType::UniformBuffer {
+                        dynamic: false,
+                        min_binding_size: None,
+                    },
+                ),
+            ],
+        });
+
+        let vs_shader = device.create_shader_module(wgpu::include_spirv!("../../shaders/sprite.vert.spv"));
+        let fs_shader = device.create_shader_module(wgpu::include_spirv!("../../shaders/sprite.frag.spv"));
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[&camera_bind_group_layout, &atlas_bind_group_layout],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            layout: &pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor {
+                module: &vs_shader,
+                entry_point: "main",
+            },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                module: &fs_shader,
+                entry_point: "main",
+            }),
+            rasterization_state: None,
+            primitive_topology: wgpu::PrimitiveTopology::TriangleStrip,
+            color_states: &[wgpu::ColorStateDescriptor {
+                format: SWAPCHAIN_FORMAT,
+                alpha_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                color_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                write_mask: wgpu::ColorWrite::ALL,
+            }],
+            depth_stencil_state: None,
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format: wgpu::IndexFormat::Uint16,
+                vertex_buffers: &[wgpu::VertexBufferDescriptor {
+                    stride: std::mem::size_of::<SpriteInstance>() as wgpu::BufferAddress,
+                    step_mode: wgpu::InputStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![0 => Float2, 1 => Float4, 2 => Uint, 3 => Uint],
+                }],
+            },
+            sample_count: 1,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        });
+
+        let camera_bind_group =
+            create_camera_bind_group(&camera_bind_group_layout, device, &glm::identity(), &glm::identity());
+
+        let atlas_bind_group = create_atlas_bind_group(
+            &atlas_bind_group_layout,
+            device,
+            utils::rgba_null_texture(device, queue),
+            &[1, 1],
+        );
+
+        Self {
+            render_pipeline,
+            camera_bind_group_layout,
+            camera_bind_group,
+            atlas_bind_group_layout,
+            atlas_bind_group,
+        }
+    }
+
+    pub fn update_camera(&mut self, device: &wgpu::Device, view: &glm::Mat4, projection: &glm::Mat4) {
+        self.camera_bind_group = create_camera_bind_group(&self.camera_bind_group_layout, device, view, projection);
+    }
+
+    pub fn update_atlas(&mut self, device: &wgpu::Device, texture_view: &wgpu::TextureView, size: &[u32; 2]) {
+        self.atlas_bind_group = create_atlas_bind_group(&self.atlas_bind_group_layout, device, texture_view, size);
+    }
+
+    pub fn create_sprite_buffer(&self, device: &wgpu::Device, batch: &SpriteBatch) -> Option<SpriteBuffer> {
+        if batch.sprites.is_empty() {
+            return None;
+        }
+
+        Some(SpriteBuffer {
+            buffer: device.create_buffer_with_data(bytemuck::cast_slice(&batch.sprites), wgpu::BufferUsage::VERTEX),
+            keys: batch.sprites.iter().map(|sprite| sprite.position[1]).collect(),
+        })
+    }
+
+    pub fn start<'a, 'p>(&'a self, pass: &'p mut wgpu::RenderPass<'a>) -> SpriteRendererPass<'a, 'p> {
+        pass.set_pipeline(&self.render_pipeline);
+        pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        pass.set_bind_group(1, &self.atlas_bind_group, &[]);
+
+        SpriteRendererPass { pass }
+    }
+}
+
+pub struct SpriteRendererPass<'a, 'p> {
+    pass: &'p mut wgpu::RenderPass<'a>,
+}
+
+impl<'a, 'p> SpriteRendererPass<'a, 'p> {
+    /// Draws sprites of the buffer in `range`, indexed in draw order
+    #[inline]
+    pub fn draw_sprites(&mut self, sprites: &'a SpriteBuffer, range: Range<u32>) {
+        self.pass.set_vertex_buffer(0, sprites.buffer.slice(..));
+        self.pass.draw(0..4, range);
+    }
+}
+
+pub struct SpriteBuffer {
+    buffer: wgpu::Buffer,
+    /// Y of every sprite in draw order
+    keys: Vec<f32>,
+}
+
+impl SpriteBuffer {
+    #[inline]
+    pub fn keys(&self) -> &[f32] {
+        &self.keys
+    }
+}
+
+/// Sprites collected for a single buffer, drawn in the order they were pushed unless sorted
+#[derive(Default)]
+pub struct SpriteBatch {
+    sprites: Vec<SpriteInstance>,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sprite centered at `position` in tiles. `frame` is a zero based index in the atlas
+    pub fn push(&mut self, position: [f32; 2], frame: u32, tint: [f32; 4], flip: [bool; 2]) {
+        let mut flags = 0;
+        if flip[0] {
+            flags |= FLIP_X;
+        }
+        if flip[1] {
+            flags |= FLIP_Y;
+        }
+
+        self.sprites.push(SpriteInstance {
+            position,
+            tint,
+            frame,
+            flip: flags,
+        });
+    }
+
+    /// Orders sprites from top to bottom, so lower sprites are drawn over higher ones
+    pub fn sort_by_y(&mut self) {
+        self.sprites.sort_by(|a, b| {
+            a.position[1]
+                .partial_cmp(&b.position[1])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+struct SpriteInstance {
+    position: [f32; 2],
+    tint: [f32; 4],
+    frame: u32,
+    flip: u32,
+}
+
+unsafe impl bytemuck::Zeroable for SpriteInstance {}
+unsafe impl bytemuck::Pod for SpriteInstance {}
+
+fn create_camera_bind_group(
+    layout: &wgpu::BindGroupLayout,
+    device: &wgpu::Device,
+    view: &glm::Mat4,
+    projection: &glm::Mat4,
+) -> wgpu::BindGroup {
+    let mut data = [0f32; 16 * 2];
+    data[..16].copy_from_slice(view.as_slice());
+    data[16..].copy_from_slice(projection.as_slice());
+
+    let camera_uniform_buffer = device.create_buffer_with_data(
+        bytemuck::cast_slice(&data),
+        wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+    );
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &layout,
+        bindings: &[wgpu::Binding {
+            binding: 0,
+            resource: wgpu::BindingResource::Buffer(camera_uniform_buffer.slice(..)),
+        }],
+        label: None,
+    })
+}
+
+fn create_atlas_bind_group(
+    layout: &wgpu::BindGroupLayout,
+    device: &wgpu::Device,
+    texture_view: &wgpu::TextureView,
+    size: &[u32; 2],
+) -> wgpu::BindGroup {
+    let atlas_uniform_buffer = device.create_buffer_with_data(
+        bytemuck::cast_slice(size),
+        wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+    );
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &layout,
+        bindings: &[
+            wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(texture_view),
+            },
+            wgpu::Binding {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(utils::pixel_sampler(device)),
+            },
+            wgpu::Binding {
+                binding: 2,
+                resource: wgpu::BindingResource::Buffer(atlas_uniform_buffer.slice(..)),
+            },
+        ],
+        label: None,
+    })
+}
+
+const FLIP_X: u32 = 0x1;
+const FLIP_Y: u32 = 0x2;
@@ -0,0 +1,253 @@
+use winit::dpi::PhysicalSize;
+
+use super::utils;
+use super::SWAPCHAIN_FORMAT;
+
+/// Draws monospace text from the ASCII font atlas in screen pixels
+pub struct TextRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    screen_bind_group_layout: wgpu::BindGroupLayout,
+    screen_bind_group: wgpu::BindGroup,
+    font_bind_group_layout: wgpu::BindGroupLayout,
+    font_bind_group: wgpu::BindGroup,
+}
+
+impl TextRenderer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, screen_size: PhysicalSize<u32>) -> Self {
+        let screen_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            bindings: &[wgpu::BindGroupLayoutEntry::new(
+                0,
+                wgpu::ShaderStage::VERTEX,
+                wgpu::BindingType::UniformBuffer {
+                    dynamic: false,
+                    min_binding_size: None,
+                },
+            )],
+        });
+
+        let font_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            bindings: &[
+                wgpu::BindGroupLayoutEntry::new(
+                    0,
+                    wgpu::ShaderStage::FRAGMENT,
+                    wgpu::BindingType::SampledTexture {
+                        dimension: wgpu::TextureViewDimension::D2,
+                        component_type: wgpu::TextureComponentType::Float,
+                        multisampled: false,
+                    },
+                ),
+                wgpu::BindGroupLayoutEntry::new(
+                    1,
+                    wgpu::ShaderStage::FRAGMENT,
+                    wgpu::BindingType::Sampler { comparison: false },
+                ),
+            ],
+        });
+
+        let vs_shader = device.create_shader_module(wgpu::include_spirv!("../../shaders/text.vert.spv"));
+        let fs_shader = device.create_shader_module(wgpu::include_spirv!("../../shaders/text.frag.spv"));
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[&screen_bind_group_layout, &font_bind_group_layout],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            layout: &pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor {
+                module: &vs_shader,
+                entry_point: "main",
+            },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                module: &fs_shader,
+                entry_point: "main",
+            }),
+            rasterization_state: None,
+            primitive_topology: wgpu::PrimitiveTopology::TriangleStrip,
+            color_states: &[wgpu::ColorStateDescriptor {
+                format: SWAPCHAIN_FORMAT,
+                alpha_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                color_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                write_mask: wgpu::ColorWrite::ALL,
+            }],
+            depth_stencil_state: None,
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format: wgpu::IndexFormat::Uint16,
+                vertex_buffers: &[wgpu::VertexBufferDescriptor {
+                    stride: std::mem::size_of::<GlyphInstance>() as wgpu::BufferAddress,
+                    step_mode: wgpu::InputStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![0 => Float2, 1 => Float4, 2 => Uint],
+                }],
+            },
+            sample_count: 1,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        });
+
+        let screen_bind_group = create_screen_bind_group(&screen_bind_group_layout, device, screen_size);
+        let font_bind_group =
+            create_font_bind_group(&font_bind_group_layout, device, utils::rgba_null_texture(device, queue));
+
+        Self {
+            render_pipeline,
+            screen_bind_group_layout,
+            screen_bind_group,
+            font_bind_group_layout,
+            font_bind_group,
+        }
+    }
+
+    pub fn update_screen_size(&mut self, device: &wgpu::Device, size: PhysicalSize<u32>) {
+        self.screen_bind_group = create_screen_bind_group(&self.screen_bind_group_layout, device, size);
+    }
+
+    pub fn update_font(&mut self, device: &wgpu::Device, texture_view: &wgpu::TextureView) {
+        self.font_bind_group = create_font_bind_group(&self.font_bind_group_layout, device, texture_view);
+    }
+
+    pub fn create_text_buffer(&self, device: &wgpu::Device, batch: &TextBatch) -> Option<TextBuffer> {
+        if batch.glyphs.is_empty() {
+            return None;
+        }
+
+        Some(TextBuffer {
+            buffer: device.create_buffer_with_data(bytemuck::cast_slice(&batch.glyphs), wgpu::BufferUsage::VERTEX),
+            glyph_count: batch.glyphs.len() as u32,
+        })
+    }
+
+    pub fn start<'a, 'p>(&'a self, pass: &'p mut wgpu::RenderPass<'a>) -> TextRendererPass<'a, 'p> {
+        pass.set_pipeline(&self.render_pipeline);
+        pass.set_bind_group(0, &self.screen_bind_group, &[]);
+        pass.set_bind_group(1, &self.font_bind_group, &[]);
+
+        TextRendererPass { pass }
+    }
+}
+
+pub struct TextRendererPass<'a, 'p> {
+    pass: &'p mut wgpu::RenderPass<'a>,
+}
+
+impl<'a, 'p> TextRendererPass<'a, 'p> {
+    #[inline]
+    pub fn draw_text(&mut self, text: &'a TextBuffer) {
+        self.pass.set_vertex_buffer(0, text.buffer.slice(..));
+        self.pass.draw(0..4, 0..text.glyph_count);
+    }
+}
+
+pub struct TextBuffer {
+    buffer: wgpu::Buffer,
+    glyph_count: u32,
+}
+
+/// Glyphs collected for a single draw call
+#[derive(Default)]
+pub struct TextBatch {
+    glyphs: Vec<GlyphInstance>,
+}
+
+impl TextBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lays out a single line starting at the top left `position`. Non-ASCII characters are drawn as `?`
+    pub fn push_text(&mut self, position: [f32; 2], color: [f32; 4], text: &str) {
+        for (i, c) in text.chars().enumerate() {
+            let glyph = match c {
+                ' '..='~' => c as u32 - ' ' as u32,
+                _ => '?' as u32 - ' ' as u32,
+            };
+
+            self.glyphs.push(GlyphInstance {
+                position: [position[0] + i as f32 * GLYPH_SIZE[0], position[1]],
+                color,
+                glyph,
+            });
+        }
+    }
+
+    /// Fills a rectangle of `size` glyph cells, used for backgrounds
+    pub fn push_rect(&mut self, position: [f32; 2], size: [u32; 2], color: [f32; 4]) {
+        for y in 0..size[1] {
+            for x in 0..size[0] {
+                self.glyphs.push(GlyphInstance {
+                    position: [
+                        position[0] + x as f32 * GLYPH_SIZE[0],
+                        position[1] + y as f32 * GLYPH_SIZE[1],
+                    ],
+                    color,
+                    glyph: SOLID_GLYPH,
+                });
+            }
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+struct GlyphInstance {
+    position: [f32; 2],
+    color: [f32; 4],
+    glyph: u32,
+}
+
+unsafe impl bytemuck::Zeroable for GlyphInstance {}
+unsafe impl bytemuck::Pod for GlyphInstance {}
+
+fn create_screen_bind_group(
+    layout: &wgpu::BindGroupLayout,
+    device: &wgpu::Device,
+    size: PhysicalSize<u32>,
+) -> wgpu::BindGroup {
+    let screen_uniform_buffer = device.create_buffer_with_data(
+        bytemuck::cast_slice(&[size.width as f32, size.height as f32]),
+        wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+    );
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &layout,
+        bindings: &[wgpu::Binding {
+            binding: 0,
+            resource: wgpu::BindingResource::Buffer(screen_uniform_buffer.slice(..)),
+        }],
+        label: None,
+    })
+}
+
+fn create_font_bind_group(
+    layout: &wgpu::BindGroupLayout,
+    device: &wgpu::Device,
+    texture_view: &wgpu::TextureView,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &layout,
+        bindings: &[
+            wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(texture_view),
+            },
+            wgpu::Binding {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(utils::pixel_sampler(device)),
+            },
+        ],
+        label: None,
+    })
+}
+
+/// Size of a glyph cell in the font atlas and on screen
+pub const GLYPH_SIZE: [f32; 2] = [8.0, 16.0];
+/// Fully opaque cell placed right after `~` in the font atlas
+const SOLID_GLYPH: u32 = 95;
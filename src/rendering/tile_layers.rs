@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use super::{SpriteBuffer, SpriteRenderer, TileMapRenderer};
+use crate::chunks::LoadedChunks;
+use crate::network::{ChunkData, ServerMessage};
+use crate::CHUNK_SIZE;
+
+/// Loaded chunks of every tile layer of the map.
+///
+/// Y sorted layers are drawn row by row with sprites in between. A sprite is drawn over the rows whose
+/// bottom edge is above its position and under the rest, so entities walk behind trees but not under their
+/// canopy. Other layers below the first sorted layer are drawn before it, and the remaining ones after it
+#[derive(Default)]
+pub struct TileLayers {
+    chunks: LoadedChunks<Chunk>,
+    y_sorted_layers: HashSet<u32>,
+}
+
+impl TileLayers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_y_sorted_layers(&mut self, layers: &[u32]) {
+        self.y_sorted_layers = layers.iter().copied().collect();
+    }
+
+    /// Loads or unloads the chunk of a chunk message, `load` creates the buffer and the bind group of new chunks
+    pub fn apply<F>(&mut self, message: &ServerMessage, load: F)
+    where
+        F: FnOnce(&ChunkData) -> (wgpu::Buffer, wgpu::BindGroup),
+    {
+        self.chunks.apply(message, |chunk| {
+            let (buffer, bind_group) = load(chunk);
+            Chunk {
+                _buffer: buffer,
+                bind_group,
+            }
+        });
+    }
+
+    /// Draws all layers together with `sprites`, which must be sorted with `SpriteBatch::sort_by_y`
+    pub fn draw<'a>(
+        &'a self,
+        pass: &mut wgpu::RenderPass<'a>,
+        tile_map_renderer: &'a TileMapRenderer,
+        sprite_renderer: &'a SpriteRenderer,
+        sprites: Option<&'a SpriteBuffer>,
+    ) {
+        let first_sorted_layer = self.y_sorted_layers.iter().min().copied();
+
+        let (sorted, unsorted): (Vec<_>, Vec<_>) = self
+            .chunks
+            .iter()
+            .partition(|((layer, _, _), _)| self.y_sorted_layers.contains(layer));
+        let (below, above): (Vec<_>, Vec<_>) = unsorted
+            .into_iter()
+            .partition(|((layer, _, _), _)| first_sorted_layer.map_or(true, |first| *layer < first));
+
+        let mut tile_map_pass = tile_map_renderer.start(pass);
+        for (_, chunk) in below {
+            tile_map_pass.draw_chunk(&chunk.bind_group);
+        }
+
+        let keys = sprites.map(|sprites| sprites.keys()).unwrap_or_default();
+        let mut drawn = 0;
+
+        let first_row = sorted.iter().map(|((_, y, _), _)| *y).min().unwrap_or_default() * CHUNK_SIZE as i32;
+        let end_row = sorted.iter().map(|((_, y, _), _)| *y + 1).max().unwrap_or_default() * CHUNK_SIZE as i32;
+
+        let mut start_row = first_row;
+        for row in first_row..end_row {
+            let mut behind = drawn;
+            while behind < keys.len() && keys[behind] < (row + 1) as f32 {
+                behind += 1;
+            }
+
+            if behind > drawn {
+                draw_rows(pass, tile_map_renderer, &sorted, start_row..row);
+                draw_sprites(pass, sprite_renderer, sprites, drawn..behind);
+                drawn = behind;
+                start_row = row;
+            }
+        }
+        draw_rows(pass, tile_map_renderer, &sorted, start_row..end_row);
+        draw_sprites(pass, sprite_renderer, sprites, drawn..keys.len());
+
+        let mut tile_map_pass = tile_map_renderer.start(pass);
+        for (_, chunk) in above {
+            tile_map_pass.draw_chunk(&chunk.bind_group);
+        }
+    }
+}
+
+struct Chunk {
+    /// Uniform buffer of the bind group
+    _buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Draws map `rows` of the given chunks
+fn draw_rows<'a>(
+    pass: &mut wgpu::RenderPass<'a>,
+    tile_map_renderer: &'a TileMapRenderer,
+    chunks: &[(&(u32, i32, i32), &'a Chunk)],
+    rows: Range<i32>,
+) {
+    if rows.start >= rows.end {
+        return;
+    }
+
+    let mut tile_map_pass = tile_map_renderer.start(pass);
+    for ((_, y, _), chunk) in chunks {
+        let top = y * CHUNK_SIZE as i32;
+        let start = (rows.start - top).max(0);
+        let end = (rows.end - top).min(CHUNK_SIZE as i32);
+
+        if start < end {
+            tile_map_pass.draw_chunk_rows(&chunk.bind_group, start as u32..end as u32);
+        }
+    }
+}
+
+fn draw_sprites<'a>(
+    pass: &mut wgpu::RenderPass<'a>,
+    sprite_renderer: &'a SpriteRenderer,
+    sprites: Option<&'a SpriteBuffer>,
+    range: Range<usize>,
+) {
+    if let Some(sprites) = sprites {
+        if range.start < range.end {
+            sprite_renderer
+                .start(pass)
+                .draw_sprites(sprites, range.start as u32..range.end as u32);
+        }
+    }
+}
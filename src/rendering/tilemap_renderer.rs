@@ -1,5 +1,9 @@
+use std::ops::Range;
+use std::time::Duration;
+
 use super::utils;
 use super::SWAPCHAIN_FORMAT;
+use crate::animation::TileAnimationTable;
 
 pub struct TileMapRenderer {
     render_pipeline: wgpu::RenderPipeline,
@@ -7,6 +11,9 @@ pub struct TileMapRenderer {
     camera_bind_group: wgpu::BindGroup,
     tileset_bind_group_layout: wgpu::BindGroupLayout,
     tileset_bind_group: wgpu::BindGroup,
+    animation_bind_group_layout: wgpu::BindGroupLayout,
+    animation_bind_group: wgpu::BindGroup,
+    time_buffer: wgpu::Buffer,
 }
 
 impl TileMapRenderer {
@@ -51,6 +58,29 @@ impl TileMapRenderer {
             ],
         });
 
+        let animation_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            bindings: &[
+                wgpu::BindGroupLayoutEntry::new(
+                    0,
+                    wgpu::ShaderStage::FRAGMENT,
+                    wgpu::BindingType::StorageBuffer {
+                        dynamic: false,
+                        min_binding_size: None,
+                        readonly: true,
+                    },
+                ),
+                wgpu::BindGroupLayoutEntry::new(
+                    1,
+                    wgpu::ShaderStage::FRAGMENT,
+                    wgpu::BindingType::UniformBuffer {
+                        dynamic: false,
+                        min_binding_size: None,
+                    },
+                ),
+            ],
+        });
+
         let vs_shader = device.create_shader_module(wgpu::include_spirv!("../../shaders/tile.vert.spv"));
         let fs_shader = device.create_shader_module(wgpu::include_spirv!("../../shaders/tile.frag.spv"));
 
@@ -59,6 +89,7 @@ impl TileMapRenderer {
                 &mesh_bind_group_layout,
                 &tileset_bind_group_layout,
                 &mesh_bind_group_layout,
+                &animation_bind_group_layout,
             ],
         });
 
@@ -108,12 +139,26 @@ impl TileMapRenderer {
             &[1, 1],
         );
 
+        let time_buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(&[0u32]),
+            wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        );
+        let animation_bind_group = create_animation_bind_group(
+            &animation_bind_group_layout,
+            device,
+            &TileAnimationTable::new(),
+            &time_buffer,
+        );
+
         Self {
             render_pipeline,
             mesh_bind_group_layout,
             camera_bind_group,
             tileset_bind_group_layout,
             tileset_bind_group,
+            animation_bind_group_layout,
+            animation_bind_group,
+            time_buffer,
         }
     }
 
@@ -126,6 +171,17 @@ impl TileMapRenderer {
             create_tileset_bind_group(&self.tileset_bind_group_layout, device, texture_view, size);
     }
 
+    pub fn update_animations(&mut self, device: &wgpu::Device, animations: &TileAnimationTable) {
+        self.animation_bind_group =
+            create_animation_bind_group(&self.animation_bind_group_layout, device, animations, &self.time_buffer);
+    }
+
+    /// Sets the time at which animated tiles are drawn
+    pub fn update_time(&self, queue: &wgpu::Queue, time: Duration) {
+        let millis = time.as_millis() as u32;
+        queue.write_buffer(&self.time_buffer, 0, bytemuck::cast_slice(&[millis]));
+    }
+
     pub fn create_chunk_bind_group(&self, device: &wgpu::Device, buffer: &wgpu::Buffer) -> wgpu::BindGroup {
         device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &self.mesh_bind_group_layout,
@@ -141,6 +197,7 @@ impl TileMapRenderer {
         pass.set_pipeline(&self.render_pipeline);
         pass.set_bind_group(0, &self.camera_bind_group, &[]);
         pass.set_bind_group(1, &self.tileset_bind_group, &[]);
+        pass.set_bind_group(3, &self.animation_bind_group, &[]);
 
         TileMapRendererPass { renderer: self, pass }
     }
@@ -157,6 +214,13 @@ impl<'a, 'e> TileMapRendererPass<'a, 'e> {
         self.pass.set_bind_group(2, data, &[]);
         self.pass.draw(0..4, 0..256);
     }
+
+    /// Draws only the given rows of the chunk, counted from its top
+    #[inline]
+    pub fn draw_chunk_rows(&mut self, data: &'a wgpu::BindGroup, rows: Range<u32>) {
+        self.pass.set_bind_group(2, data, &[]);
+        self.pass.draw(0..4, rows.start * 16..rows.end * 16);
+    }
 }
 
 fn create_camera_bind_group(
@@ -185,6 +249,31 @@ fn create_mesh_bind_group(layout: &wgpu::BindGroupLayout, device: &wgpu::Device,
     })
 }
 
+fn create_animation_bind_group(
+    layout: &wgpu::BindGroupLayout,
+    device: &wgpu::Device,
+    animations: &TileAnimationTable,
+    time_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    let animation_storage_buffer =
+        device.create_buffer_with_data(bytemuck::cast_slice(&animations.to_data()), wgpu::BufferUsage::STORAGE);
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &layout,
+        bindings: &[
+            wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(animation_storage_buffer.slice(..)),
+            },
+            wgpu::Binding {
+                binding: 1,
+                resource: wgpu::BindingResource::Buffer(time_buffer.slice(..)),
+            },
+        ],
+        label: None,
+    })
+}
+
 fn create_tileset_bind_group(
     layout: &wgpu::BindGroupLayout,
     device: &wgpu::Device,
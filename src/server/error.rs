@@ -0,0 +1,14 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Unsupported map type")]
+    UnsupportedMapType,
+
+    #[error("No tilesets found")]
+    NoTilesets,
+
+    #[error("Invalid layer data: {0}")]
+    InvalidLayerData(String),
+
+    #[error("Session not found: {0}")]
+    SessionNotFound(uuid::Uuid),
+}
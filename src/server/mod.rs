@@ -0,0 +1,402 @@
+mod error;
+mod tiled;
+
+pub use self::error::*;
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use embercore::tme;
+
+use self::tiled::{ObjectLayers, TilesetProperties};
+use crate::collision::CollisionGrid;
+use crate::network::{
+    self, framed, timestamp, ChunkData, ClientMessage, EntityId, EntityState, Link, MapObject, RejectionReason,
+    ServerMessage, SnapshotSender, UdpListener, MAX_CHAT_MESSAGE_LENGTH, PROTOCOL_VERSION,
+};
+use crate::resources;
+use crate::CHUNK_SIZE;
+
+/// Minimal game server for development and tests.
+///
+/// Accepts any credentials, serves the map from the content directory as chunks
+/// and echoes player movement back as authoritative state. Listens for both TCP and UDP on the same port
+pub struct StandInServer {
+    listener: TcpListener,
+    udp_listener: UdpListener,
+    state: Arc<ServerState>,
+}
+
+struct ServerState {
+    map: MapData,
+    sessions: Mutex<HashMap<Uuid, Player>>,
+    next_entity: AtomicU32,
+    chat: broadcast::Sender<ServerMessage>,
+}
+
+struct Player {
+    login: String,
+    entity: EntityId,
+    position: glm::Vec2,
+    online: bool,
+}
+
+impl StandInServer {
+    /// UDP clients are disconnected after `timeout` without any packets
+    pub async fn bind(address: SocketAddr, content_dir: &Path, timeout: Duration) -> Result<Self> {
+        let map = load_map(content_dir)?;
+        let listener = TcpListener::bind(address).await?;
+        let udp_listener = UdpListener::bind(listener.local_addr()?, timeout).await?;
+
+        Ok(Self {
+            listener,
+            udp_listener,
+            state: Arc::new(ServerState {
+                map,
+                sessions: Mutex::new(HashMap::new()),
+                next_entity: AtomicU32::new(0),
+                chat: broadcast::channel(CHAT_CAPACITY).0,
+            }),
+        })
+    }
+
+    #[inline]
+    pub fn local_address(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    pub async fn run(mut self) -> Result<()> {
+        loop {
+            let (link, address) = tokio::select! {
+                accepted = self.listener.accept() => {
+                    let (stream, address) = accepted?;
+                    stream.set_nodelay(true)?;
+                    (Link::Tcp(framed(stream)), address)
+                }
+                accepted = self.udp_listener.accept() => {
+                    let (transport, address) = accepted?;
+                    (Link::Udp(transport), address)
+                }
+            };
+            log::info!("Client connected: {}", address);
+
+            let state = self.state.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_client(link, &state).await {
+                    log::warn!("Client {} disconnected: {:?}", address, e);
+                }
+            });
+        }
+    }
+}
+
+async fn handle_client(mut link: Link<ClientMessage, ServerMessage>, state: &ServerState) -> Result<()> {
+    let session = match receive(&mut link).await? {
+        Some(ClientMessage::Hello {
+            protocol_version,
+            credentials,
+            session,
+        }) => {
+            if protocol_version != PROTOCOL_VERSION {
+                let reason = RejectionReason::ProtocolVersionMismatch {
+                    server_version: PROTOCOL_VERSION,
+                };
+                link.send(ServerMessage::Rejected { reason }).await?;
+                return Ok(());
+            }
+
+            let mut sessions = state.sessions.lock().unwrap();
+            let session = match session {
+                Some(session) if sessions.contains_key(&session) => session,
+                _ => {
+                    let session = Uuid::new_v4();
+                    sessions.insert(
+                        session,
+                        Player {
+                            login: credentials.login,
+                            entity: state.next_entity.fetch_add(1, Ordering::Relaxed),
+                            position: state.map.spawn_position,
+                            online: false,
+                        },
+                    );
+                    session
+                }
+            };
+            if let Some(player) = sessions.get_mut(&session) {
+                player.online = true;
+            }
+            session
+        }
+        Some(message) => return Err(network::Error::UnexpectedMessage(format!("{:?}", message)).into()),
+        None => return Ok(()),
+    };
+
+    let result = serve_player(&mut link, state, session).await;
+
+    if let Some(player) = state.sessions.lock().unwrap().get_mut(&session) {
+        player.online = false;
+    }
+
+    result
+}
+
+async fn serve_player(link: &mut Link<ClientMessage, ServerMessage>, state: &ServerState, session: Uuid) -> Result<()> {
+    link.send(ServerMessage::Welcome {
+        protocol_version: PROTOCOL_VERSION,
+        session,
+    })
+    .await?;
+
+    let player_position = match state.sessions.lock().unwrap().get(&session) {
+        Some(player) => player.position,
+        None => return Err(Error::SessionNotFound(session).into()),
+    };
+
+    link.send(ServerMessage::MapInfo {
+        width: state.map.collision.width(),
+        height: state.map.collision.height(),
+        collision: state.map.collision.clone(),
+        objects: state.map.objects.clone(),
+        y_sorted_layers: state.map.y_sorted_layers.clone(),
+        player_position: [player_position.x, player_position.y],
+    })
+    .await?;
+
+    for chunk in state.map.chunks.iter() {
+        link.send(ServerMessage::ChunkLoaded(chunk.clone())).await?;
+    }
+
+    let mut snapshots = SnapshotSender::new();
+    let mut snapshot_interval = tokio::time::interval(SNAPSHOT_INTERVAL);
+    let mut chat = state.chat.subscribe();
+
+    loop {
+        tokio::select! {
+            message = receive(link) => {
+                let message = match message? {
+                    Some(message) => message,
+                    None => return Ok(()),
+                };
+
+                let response = match message {
+                    ClientMessage::Ping { id, client_time } => ServerMessage::Pong {
+                        id,
+                        client_time,
+                        server_time: Utc::now(),
+                    },
+                    ClientMessage::Input(command) => {
+                        let mut sessions = state.sessions.lock().unwrap();
+                        let player = sessions.get_mut(&session).ok_or(Error::SessionNotFound(session))?;
+                        player.position = command.apply(&player.position, Some(&state.map.collision));
+
+                        ServerMessage::PlayerState {
+                            last_input: command.sequence,
+                            position: [player.position.x, player.position.y],
+                        }
+                    }
+                    ClientMessage::SnapshotAck { sequence } => {
+                        snapshots.acknowledge(sequence);
+                        continue;
+                    }
+                    ClientMessage::RequestFullSnapshot => {
+                        snapshots.reset();
+                        continue;
+                    }
+                    ClientMessage::Chat { text } => {
+                        let text = text.trim().chars().take(MAX_CHAT_MESSAGE_LENGTH).collect::<String>();
+                        if text.is_empty() {
+                            continue;
+                        }
+
+                        let from = match state.sessions.lock().unwrap().get(&session) {
+                            Some(player) => player.login.clone(),
+                            None => return Err(Error::SessionNotFound(session).into()),
+                        };
+
+                        // Relayed to every player including the sender
+                        let _ = state.chat.send(ServerMessage::Chat {
+                            from,
+                            text,
+                            time: Utc::now(),
+                        });
+                        continue;
+                    }
+                    message => return Err(network::Error::UnexpectedMessage(format!("{:?}", message)).into()),
+                };
+
+                link.send(response).await?;
+            }
+            _ = snapshot_interval.tick() => {
+                // Other online players are the only entities for now
+                let entities = state
+                    .sessions
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|(id, player)| **id != session && player.online)
+                    .map(|(_, player)| EntityState {
+                        id: player.entity,
+                        position: [player.position.x, player.position.y],
+                    })
+                    .collect::<Vec<_>>();
+
+                let delta = snapshots.encode(timestamp(Utc::now()), entities);
+                link.send(ServerMessage::Snapshot(delta)).await?;
+            }
+            message = chat.recv() => match message {
+                Ok(message) => link.send(message).await?,
+                Err(broadcast::RecvError::Lagged(count)) => log::warn!("Skipped {} chat messages", count),
+                Err(broadcast::RecvError::Closed) => {}
+            },
+        }
+    }
+}
+
+/// Receives the next message, `None` when the client has disconnected
+async fn receive(link: &mut Link<ClientMessage, ServerMessage>) -> Result<Option<ClientMessage>> {
+    match link.receive().await {
+        Ok(message) => Ok(Some(message)),
+        Err(e) => match e.downcast::<network::Error>() {
+            Ok(network::Error::ConnectionClosed) => Ok(None),
+            Ok(e) => Err(e.into()),
+            Err(e) => Err(e),
+        },
+    }
+}
+
+struct MapData {
+    collision: CollisionGrid,
+    chunks: Vec<ChunkData>,
+    objects: Vec<MapObject>,
+    y_sorted_layers: Vec<u32>,
+    spawn_position: glm::Vec2,
+}
+
+/// Loads blocked tiles and objects of the map and splits its visible tile layers into chunks.
+///
+/// Every tile of the `Collision` layer is blocked, as are tiles with the `collides` property in the tileset.
+/// The `Trees` layer is sorted with entities by Y. Players spawn at the first `spawn_point` object,
+/// spawn points are not sent to clients
+fn load_map(content_dir: &Path) -> Result<MapData> {
+    let map_path = content_dir.join("tilemap.json");
+    let map = match resources::load_json(&map_path)? {
+        tme::Map::Orthogonal(map) => map,
+        _ => return Err(Error::UnsupportedMapType.into()),
+    };
+
+    let (tileset_first_gid, tileset_source) = match map.tile_sets.first() {
+        Some(tme::TilesetContainer::TilesetRef(tileset_ref)) => (tileset_ref.first_gid, &tileset_ref.source),
+        _ => return Err(Error::NoTilesets.into()),
+    };
+
+    let colliding_tiles = resources::load_json::<TilesetProperties>(&content_dir.join(tileset_source))?
+        .tiles
+        .into_iter()
+        .filter(|tile| {
+            tile.properties
+                .iter()
+                .any(|property| property.name == COLLIDES_PROPERTY && property.value == serde_json::Value::Bool(true))
+        })
+        .map(|tile| tile.id + tileset_first_gid)
+        .collect::<HashSet<_>>();
+
+    let mut collision = CollisionGrid::new(map.width as u32, map.height as u32);
+
+    let chunks_in_column = (map.height as usize + CHUNK_SIZE - 1) / CHUNK_SIZE;
+    let chunks_in_row = (map.width as usize + CHUNK_SIZE - 1) / CHUNK_SIZE;
+
+    let mut chunks = Vec::new();
+    let mut y_sorted_layers = Vec::new();
+
+    let tile_layers = map.layers.iter().filter_map(|item| {
+        if let tme::Layer::TileLayer(tile_layer) = item {
+            Some(tile_layer)
+        } else {
+            None
+        }
+    });
+
+    for (layer_index, layer) in tile_layers.enumerate() {
+        let tiles = layer
+            .data
+            .extract_tiles(layer.compression)
+            .map_err(|e| Error::InvalidLayerData(format!("{:?}", e)))?;
+
+        let is_collision_layer = layer.name == COLLISION_LAYER;
+        for (index, tile) in tiles.iter().enumerate() {
+            if *tile != 0 && (is_collision_layer || colliding_tiles.contains(tile)) {
+                collision.set_blocked(index as u32 % collision.width(), index as u32 / collision.width(), true);
+            }
+        }
+
+        // The collision layer is not drawn
+        if is_collision_layer {
+            continue;
+        }
+
+        if layer.name == Y_SORTED_LAYER {
+            y_sorted_layers.push(layer_index as u32);
+        }
+
+        for chunk_y in 0..chunks_in_column {
+            for chunk_x in 0..chunks_in_row {
+                let mut chunk = vec![0u16; CHUNK_SIZE * CHUNK_SIZE];
+
+                let max_y = std::cmp::min((chunk_y + 1) * CHUNK_SIZE, map.height as usize) - chunk_y * CHUNK_SIZE;
+                let max_x = std::cmp::min((chunk_x + 1) * CHUNK_SIZE, map.width as usize) - chunk_x * CHUNK_SIZE;
+
+                for y in 0..max_y {
+                    for x in 0..max_x {
+                        let tile_index = (chunk_y * CHUNK_SIZE + y) * map.width as usize + chunk_x * CHUNK_SIZE + x;
+                        chunk[y * CHUNK_SIZE + x] = match tiles[tile_index] {
+                            0 => 0,
+                            tile => (tile + 1 - tileset_first_gid) as u16,
+                        };
+                    }
+                }
+
+                chunks.push(ChunkData {
+                    layer: layer_index as u32,
+                    x: chunk_x as i32,
+                    y: chunk_y as i32,
+                    tiles: chunk,
+                });
+            }
+        }
+    }
+
+    let mut objects = resources::load_json::<ObjectLayers>(&map_path)?.objects();
+    let spawn_position = match objects.iter().find(|object| object.kind == SPAWN_POINT_TYPE) {
+        Some(object) => glm::vec2(object.position[0], object.position[1]),
+        None => glm::vec2(SPAWN_POSITION[0], SPAWN_POSITION[1]),
+    };
+    // Clients get the position of their player instead
+    objects.retain(|object| object.kind != SPAWN_POINT_TYPE);
+
+    Ok(MapData {
+        collision,
+        chunks,
+        objects,
+        y_sorted_layers,
+        spawn_position,
+    })
+}
+
+const COLLISION_LAYER: &str = "Collision";
+const Y_SORTED_LAYER: &str = "Trees";
+const COLLIDES_PROPERTY: &str = "collides";
+const SPAWN_POINT_TYPE: &str = "spawn_point";
+/// Used when the map has no spawn points
+const SPAWN_POSITION: [f32; 2] = [8.0, 8.0];
+const SNAPSHOT_INTERVAL: Duration = Duration::from_millis(50);
+const CHAT_CAPACITY: usize = 64;
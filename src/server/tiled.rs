@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::network::{MapObject, PropertyValue};
+
+/// Custom tile properties of a Tiled tileset
+#[derive(Deserialize)]
+pub struct TilesetProperties {
+    #[serde(default)]
+    pub tiles: Vec<TileProperties>,
+}
+
+#[derive(Deserialize)]
+pub struct TileProperties {
+    pub id: u32,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Deserialize)]
+pub struct Property {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    pub value: serde_json::Value,
+}
+
+impl Property {
+    pub fn to_value(&self) -> Option<PropertyValue> {
+        match &self.value {
+            serde_json::Value::Bool(value) => Some(PropertyValue::Bool(*value)),
+            serde_json::Value::Number(value) => match value.as_i64() {
+                Some(value) if self.kind != "float" => Some(PropertyValue::Int(value)),
+                _ => value.as_f64().map(PropertyValue::Float),
+            },
+            serde_json::Value::String(value) => Some(PropertyValue::String(value.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Object layers of a Tiled map, tile layers are loaded with `tme`
+#[derive(Deserialize)]
+pub struct ObjectLayers {
+    #[serde(rename = "tilewidth")]
+    tile_width: f32,
+    #[serde(rename = "tileheight")]
+    tile_height: f32,
+    layers: Vec<Layer>,
+}
+
+#[derive(Deserialize)]
+struct Layer {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    objects: Vec<Object>,
+    /// Children of group layers
+    #[serde(default)]
+    layers: Vec<Layer>,
+}
+
+#[derive(Deserialize)]
+struct Object {
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    /// Only set for tile objects, which are anchored at the bottom left corner
+    #[serde(default)]
+    gid: Option<u32>,
+    #[serde(default)]
+    properties: Vec<Property>,
+}
+
+impl ObjectLayers {
+    /// Objects of every object layer, including layers nested in groups
+    pub fn objects(&self) -> Vec<MapObject> {
+        let mut objects = Vec::new();
+        self.collect_objects(&self.layers, &mut objects);
+        objects
+    }
+
+    fn collect_objects(&self, layers: &[Layer], objects: &mut Vec<MapObject>) {
+        for layer in layers {
+            match layer.kind.as_str() {
+                "objectgroup" => objects.extend(layer.objects.iter().map(|object| self.convert(object))),
+                "group" => self.collect_objects(&layer.layers, objects),
+                "tilelayer" => {}
+                kind => log::warn!("Skipping layer {} of unsupported type {}", layer.name, kind),
+            }
+        }
+    }
+
+    fn convert(&self, object: &Object) -> MapObject {
+        let top = match object.gid {
+            Some(_) => object.y - object.height,
+            None => object.y,
+        };
+
+        let properties = object
+            .properties
+            .iter()
+            .filter_map(|property| match property.to_value() {
+                Some(value) => Some((property.name.clone(), value)),
+                None => {
+                    log::warn!("Skipping property {} of object {}", property.name, object.id);
+                    None
+                }
+            })
+            .collect::<HashMap<_, _>>();
+
+        MapObject {
+            id: object.id,
+            name: object.name.clone(),
+            kind: object.kind.clone(),
+            position: [
+                (object.x + object.width / 2.0) / self.tile_width,
+                (top + object.height / 2.0) / self.tile_height,
+            ],
+            size: [object.width / self.tile_width, object.height / self.tile_height],
+            properties,
+        }
+    }
+}
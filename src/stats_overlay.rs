@@ -0,0 +1,53 @@
+use winit::event::VirtualKeyCode;
+
+use crate::input::InputState;
+use crate::network::{Millis, NetStats, NetStatsReport};
+use crate::rendering::{TextBatch, GLYPH_SIZE};
+
+/// Connection statistics in the top left corner of the screen, toggled with F3
+pub struct StatsOverlay {
+    visible: bool,
+}
+
+impl StatsOverlay {
+    pub fn new() -> Self {
+        Self { visible: false }
+    }
+
+    pub fn update(&mut self, input_state: &InputState) {
+        if input_state.keyboard().was_pressed(VirtualKeyCode::F3) {
+            self.visible = !self.visible;
+        }
+    }
+
+    pub fn layout(&self, batch: &mut TextBatch, stats: &NetStats) {
+        if !self.visible {
+            return;
+        }
+
+        let lines = format_report(&stats.report());
+
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or_default() as u32 + 2;
+        batch.push_rect([MARGIN, MARGIN], [width, lines.len() as u32], BACKGROUND_COLOR);
+
+        for (i, line) in lines.iter().enumerate() {
+            let position = [MARGIN + GLYPH_SIZE[0], MARGIN + i as f32 * GLYPH_SIZE[1]];
+            batch.push_text(position, TEXT_COLOR, line);
+        }
+    }
+}
+
+fn format_report(report: &NetStatsReport) -> Vec<String> {
+    vec![
+        format!("Ping:    {}", Millis(report.rtt)),
+        format!("Jitter:  {}", Millis(report.jitter)),
+        format!("In:      {:.1} KB/s", report.bytes_in_per_second / 1024.0),
+        format!("Out:     {:.1} KB/s", report.bytes_out_per_second / 1024.0),
+        format!("Dropped: {}", report.dropped_packets),
+        format!("Late:    {}", report.late_packets),
+    ]
+}
+
+const MARGIN: f32 = 8.0;
+const TEXT_COLOR: [f32; 4] = [0.6, 1.0, 0.6, 1.0];
+const BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+
+use embercore_client_lib::animation::{Animation, AnimationFrame, Animations, TileAnimationTable};
+
+fn animation(frames: &[(u32, f32)]) -> Animation {
+    Animation::new(
+        frames
+            .iter()
+            .map(|&(frame, duration)| AnimationFrame { frame, duration })
+            .collect(),
+    )
+}
+
+/// Tile drawn instead of `tile` at `time` in milliseconds, looked up in the table data the same way as `tile.frag`
+fn frame_for(data: &[u32], tile: u32, time: u32) -> u32 {
+    if tile >= data[0] {
+        return tile;
+    }
+
+    let offset = data[1 + tile as usize] as usize;
+    if offset == 0 {
+        return tile;
+    }
+
+    let mut time = time % data[offset];
+    for frame in data[offset + 2..offset + 2 + 2 * data[offset + 1] as usize].chunks(2) {
+        if time < frame[1] {
+            return frame[0];
+        }
+        time -= frame[1];
+    }
+    tile
+}
+
+fn load(json: &str) -> Animations {
+    let path = std::env::temp_dir().join(format!("embercore-tileset-{}.json", std::process::id()));
+    std::fs::write(&path, json).unwrap();
+
+    let animations = Animations::load(&path);
+    let _ = std::fs::remove_file(&path);
+    animations.unwrap()
+}
+
+const TILESET: &str = r#"{
+    "tiles": [
+        {
+            "id": 3,
+            "animation": [{ "tileid": 3, "duration": 100 }, { "tileid": 4, "duration": 300 }],
+            "properties": [
+                { "name": "character", "type": "string", "value": "player" },
+                { "name": "clip", "type": "string", "value": "walk_down" }
+            ]
+        },
+        {
+            "id": 7,
+            "animation": [{ "tileid": 8, "duration": 250 }]
+        },
+        {
+            "id": 16,
+            "properties": [{ "name": "collides", "type": "bool", "value": true }]
+        }
+    ]
+}"#;
+
+#[test]
+fn frames_follow_their_durations_and_wrap_around() {
+    let animation = animation(&[(1, 0.1), (2, 0.3)]);
+
+    assert_eq!(animation.frame_at(0.0), Some(1));
+    assert_eq!(animation.frame_at(0.05), Some(1));
+    assert_eq!(animation.frame_at(0.15), Some(2));
+    assert_eq!(animation.frame_at(0.35), Some(2));
+    assert_eq!(animation.frame_at(0.45), Some(1));
+    assert_eq!(animation.frame_at(10.25), Some(2));
+}
+
+#[test]
+fn zero_duration_frames_are_never_shown() {
+    let skipping = animation(&[(1, 0.0), (2, 0.2), (3, 0.0)]);
+    assert_eq!(skipping.frame_at(0.0), Some(2));
+    assert_eq!(skipping.frame_at(0.3), Some(2));
+
+    // Without any duration the first frame stays
+    let still = animation(&[(4, 0.0), (5, 0.0)]);
+    assert_eq!(still.frame_at(1.0), Some(4));
+
+    assert_eq!(animation(&[]).frame_at(1.0), None);
+}
+
+#[test]
+fn tiles_with_animations_are_loaded() {
+    let animations = load(TILESET);
+
+    let frames = animations
+        .get(3)
+        .unwrap()
+        .frames()
+        .iter()
+        .map(|frame| (frame.frame, frame.duration))
+        .collect::<Vec<_>>();
+    assert_eq!(frames, [(3, 0.1), (4, 0.3)]);
+    assert_eq!(animations.get(7).and_then(|animation| animation.frame_at(1.0)), Some(8));
+    assert_eq!(animations.get(16), None);
+    assert_eq!(animations.iter().count(), 2);
+
+    // Only tiles with both character and clip properties are clips
+    assert_eq!(animations.clip("player", "walk_down"), Some(3));
+    assert_eq!(animations.clip("player", "walk_up"), None);
+    assert_eq!(animations.clip("npc", "walk_down"), None);
+}
+
+#[test]
+fn bundled_tileset_has_player_walk_clips() {
+    let animations = Animations::load(&PathBuf::from("content/tileset.json")).unwrap();
+
+    let mut clip_frames = Vec::<Vec<u32>>::new();
+    for clip in &["walk_up", "walk_down", "walk_left", "walk_right"] {
+        let tile = animations.clip("player", clip).unwrap();
+        let frames = animations.get(tile).unwrap().frames();
+        assert!(frames.len() > 1, "{} is not animated", clip);
+
+        // Every direction has its own frames
+        let frames = frames.iter().map(|frame| frame.frame).collect::<Vec<_>>();
+        for other in &clip_frames {
+            assert!(
+                frames.iter().all(|frame| !other.contains(frame)),
+                "{} shares frames",
+                clip
+            );
+        }
+        clip_frames.push(frames);
+    }
+}
+
+#[test]
+fn animation_table_resolves_frames_like_the_shader() {
+    let mut table = TileAnimationTable::new();
+    table.insert(2, &[(5, 0.1), (6, 0.3)]);
+    let data = table.to_data();
+
+    assert_eq!(frame_for(&data, 2, 0), 5);
+    assert_eq!(frame_for(&data, 2, 99), 5);
+    assert_eq!(frame_for(&data, 2, 100), 6);
+    assert_eq!(frame_for(&data, 2, 399), 6);
+    assert_eq!(frame_for(&data, 2, 400), 5);
+    assert_eq!(frame_for(&data, 2, 10_250), 6);
+
+    // Tiles without animations are drawn as they are
+    assert_eq!(frame_for(&data, 1, 100), 1);
+    assert_eq!(frame_for(&data, 10, 100), 10);
+}
+
+#[test]
+fn animation_table_layout() {
+    let mut table = TileAnimationTable::new();
+    assert_eq!(table.to_data(), [0]);
+
+    table.insert(1, &[(3, 0.1), (4, 0.2)]);
+    table.insert(0, &[(5, 0.0), (6, 0.05)]);
+    let data = table.to_data();
+    assert_eq!(data, [2, 9, 3, 300, 2, 3, 100, 4, 200, 50, 2, 5, 0, 6, 50]);
+    assert_eq!(frame_for(&data, 0, 20), 6);
+
+    // Animations without any duration are skipped
+    table.insert(3, &[(7, 0.0)]);
+    assert_eq!(frame_for(&table.to_data(), 3, 0), 3);
+}
+
+#[test]
+fn bundled_tileset_animates_map_tiles() {
+    let animations = Animations::load(&PathBuf::from("content/tileset.json")).unwrap();
+    let data = TileAnimationTable::from_animations(&animations).to_data();
+
+    assert_eq!(frame_for(&data, 52, 0), 52);
+    assert_eq!(frame_for(&data, 52, 500), 53);
+    assert_eq!(frame_for(&data, 0, 500), 0);
+}
@@ -0,0 +1,78 @@
+use futures::{SinkExt, StreamExt};
+
+use embercore_client_lib::chunks::LoadedChunks;
+use embercore_client_lib::network::*;
+use embercore_client_lib::CHUNK_SIZE;
+
+mod common;
+
+fn chunk(layer: u32, x: i32, y: i32, tile: u16) -> ServerMessage {
+    ServerMessage::ChunkLoaded(ChunkData {
+        layer,
+        x,
+        y,
+        tiles: vec![tile; CHUNK_SIZE * CHUNK_SIZE],
+    })
+}
+
+/// Applies the next `count` messages of the server, chunks keep their first tile
+async fn apply_messages(network: &mut NetworkClient, chunks: &mut LoadedChunks<u16>, count: usize) {
+    let mut applied = 0;
+    while applied < count {
+        match common::next_event(network).await {
+            NetworkEvent::StateChanged(_) => {}
+            NetworkEvent::Message(message) => {
+                chunks.apply(&message, |chunk| chunk.tiles[0]);
+                applied += 1;
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+}
+
+#[tokio::test]
+async fn streamed_chunks_are_loaded_and_unloaded() {
+    let address = common::spawn_server(|mut transport| async move {
+        common::welcome(&mut transport).await;
+
+        let messages = vec![
+            chunk(0, 0, 0, 1),
+            chunk(0, 1, 0, 2),
+            chunk(1, 0, 0, 3),
+            // Replaces the first chunk
+            chunk(0, 0, 0, 4),
+            ServerMessage::ChunkLoaded(ChunkData {
+                layer: 2,
+                x: 0,
+                y: 0,
+                tiles: vec![5; 3],
+            }),
+            ServerMessage::ChunkUnloaded { layer: 1, x: 0, y: 0 },
+            ServerMessage::ChunkUnloaded { layer: 0, x: 5, y: 5 },
+        ];
+        for message in messages {
+            transport.send(message).await.unwrap();
+        }
+
+        while let Some(Ok(_)) = transport.next().await {}
+    })
+    .await;
+
+    let mut network = NetworkClient::spawn(&common::config(address));
+    let mut chunks = LoadedChunks::new();
+
+    apply_messages(&mut network, &mut chunks, 5).await;
+
+    // Chunks with a wrong number of tiles are skipped
+    assert_eq!(chunks.layers(), [0, 1]);
+    let loaded = chunks.iter().map(|(key, tile)| (*key, *tile)).collect::<Vec<_>>();
+    assert_eq!(loaded, [((0, 0, 0), 4), ((0, 0, 1), 2), ((1, 0, 0), 3)]);
+
+    apply_messages(&mut network, &mut chunks, 2).await;
+
+    assert_eq!(chunks.layers(), [0]);
+    assert!(!chunks.contains(1, 0, 0));
+    assert!(chunks.contains(0, 0, 0));
+    assert!(chunks.contains(0, 1, 0));
+    assert_eq!(chunks.len(), 2);
+}
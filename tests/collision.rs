@@ -0,0 +1,119 @@
+use nalgebra_glm as glm;
+
+use embercore_client_lib::collision::CollisionGrid;
+use embercore_client_lib::network::{Prediction, PLAYER_HALF_SIZE};
+
+const HALF_SIZE: f32 = 0.3;
+
+fn grid_with_wall(x: u32) -> CollisionGrid {
+    let mut grid = CollisionGrid::new(16, 16);
+    for y in 0..16 {
+        grid.set_blocked(x, y, true);
+    }
+    grid
+}
+
+fn move_box(grid: &CollisionGrid, position: [f32; 2], delta: [f32; 2]) -> [f32; 2] {
+    let position = grid.move_and_slide(
+        glm::vec2(position[0], position[1]),
+        glm::vec2(HALF_SIZE, HALF_SIZE),
+        glm::vec2(delta[0], delta[1]),
+    );
+    [position.x, position.y]
+}
+
+fn assert_near(actual: [f32; 2], expected: [f32; 2]) {
+    assert!(
+        (actual[0] - expected[0]).abs() < 1e-3 && (actual[1] - expected[1]).abs() < 1e-3,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn free_movement_is_not_changed() {
+    let grid = CollisionGrid::new(16, 16);
+    assert_near(move_box(&grid, [4.5, 4.5], [1.25, -0.5]), [5.75, 4.0]);
+}
+
+#[test]
+fn outside_of_the_map_is_blocked() {
+    let mut grid = CollisionGrid::new(4, 4);
+    grid.set_blocked(10, 10, true);
+
+    assert!(grid.is_blocked(-1, 0));
+    assert!(grid.is_blocked(0, 4));
+    assert!(!grid.is_blocked(3, 3));
+
+    assert_near(move_box(&grid, [0.5, 0.5], [-2.0, -2.0]), [HALF_SIZE, HALF_SIZE]);
+    assert_near(
+        move_box(&grid, [3.5, 3.5], [2.0, 2.0]),
+        [4.0 - HALF_SIZE, 4.0 - HALF_SIZE],
+    );
+}
+
+#[test]
+fn box_stops_at_walls_in_both_directions() {
+    let grid = grid_with_wall(5);
+
+    assert_near(move_box(&grid, [4.5, 8.0], [0.5, 0.0]), [5.0 - HALF_SIZE, 8.0]);
+    assert_near(move_box(&grid, [6.5, 8.0], [-0.5, 0.0]), [6.0 + HALF_SIZE, 8.0]);
+}
+
+#[test]
+fn box_slides_along_walls() {
+    let grid = grid_with_wall(5);
+
+    assert_near(move_box(&grid, [4.5, 8.0], [1.0, 1.0]), [5.0 - HALF_SIZE, 9.0]);
+
+    // Resting exactly against the wall must not block movement along it
+    let resting = move_box(&grid, [4.5, 8.0], [1.0, 0.0]);
+    assert_near(move_box(&grid, resting, [0.0, -2.0]), [5.0 - HALF_SIZE, 6.0]);
+    assert_near(move_box(&grid, resting, [1.0, 0.0]), resting);
+}
+
+#[test]
+fn fast_movement_does_not_tunnel_through_walls() {
+    let grid = grid_with_wall(5);
+
+    assert_near(move_box(&grid, [1.5, 8.0], [10.0, 0.0]), [5.0 - HALF_SIZE, 8.0]);
+    assert_near(move_box(&grid, [12.5, 8.0], [-10.0, 0.0]), [6.0 + HALF_SIZE, 8.0]);
+}
+
+#[test]
+fn corners_are_resolved_per_axis() {
+    let mut grid = CollisionGrid::new(16, 16);
+    grid.set_blocked(5, 5, true);
+
+    // Horizontal movement clears the block, then vertical movement hits it from above
+    assert_near(move_box(&grid, [4.5, 4.5], [1.0, 1.0]), [5.5, 5.0 - HALF_SIZE]);
+
+    // Once partly past the corner on the first axis, the second one is blocked
+    assert_near(move_box(&grid, [4.5, 4.5], [0.5, 0.5]), [5.0, 5.0 - HALF_SIZE]);
+
+    // Moving into an inner corner stops on both axes
+    let mut grid = CollisionGrid::new(16, 16);
+    grid.set_blocked(5, 4, true);
+    grid.set_blocked(4, 5, true);
+    assert_near(
+        move_box(&grid, [4.5, 4.5], [1.0, 1.0]),
+        [5.0 - HALF_SIZE, 5.0 - HALF_SIZE],
+    );
+}
+
+#[test]
+fn prediction_collides_like_the_server() {
+    let grid = grid_with_wall(9);
+
+    let mut prediction = Prediction::new(glm::vec2(8.0, 8.0));
+    prediction.set_collision(grid.clone());
+    let command = prediction.apply_input(glm::vec2(1.0, 0.0), 0.5);
+
+    let server_position = command.apply(&glm::vec2(8.0, 8.0), Some(&grid));
+    assert_eq!(prediction.position(), &server_position);
+    assert!(server_position.x <= 9.0 - PLAYER_HALF_SIZE);
+
+    // Without the map movement is not blocked
+    assert!(command.apply(&glm::vec2(8.0, 8.0), None).x > 9.0);
+}
@@ -0,0 +1,95 @@
+//! Servers and client setup shared by the integration tests
+#![allow(dead_code)]
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+use embercore_client_lib::config::Config;
+use embercore_client_lib::network::*;
+use embercore_client_lib::server::StandInServer;
+
+pub type ServerTransport = Transport<ClientMessage, ServerMessage>;
+
+/// Time without any packets after which UDP connections are lost
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Accepts a single client and runs `script` with its transport
+pub async fn spawn_server<F, Fut>(script: F) -> SocketAddr
+where
+    F: FnOnce(ServerTransport) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        script(framed::<ClientMessage, ServerMessage>(stream)).await;
+    });
+
+    address
+}
+
+/// Waits for the hello of the client and starts a new session
+pub async fn welcome(transport: &mut ServerTransport) {
+    match transport.next().await {
+        Some(Ok(ClientMessage::Hello { .. })) => {}
+        message => panic!("Unexpected message: {:?}", message),
+    }
+    transport
+        .send(ServerMessage::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            session: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+}
+
+/// Stand-in server with the bundled content
+pub async fn spawn_stand_in_server() -> SocketAddr {
+    let server = StandInServer::bind("127.0.0.1:0".parse().unwrap(), Path::new("content"), TIMEOUT)
+        .await
+        .unwrap();
+    let address = server.local_address().unwrap();
+
+    tokio::spawn(server.run());
+
+    address
+}
+
+pub fn credentials() -> Credentials {
+    Credentials {
+        login: "player".to_owned(),
+        password: "player".to_owned(),
+    }
+}
+
+/// Local config with the server at `address`
+pub fn config(address: SocketAddr) -> Config {
+    let mut config = Config::new("configs/client.local.yml").unwrap();
+    config.server_address = address;
+    config
+}
+
+/// Connects and logs in with a new session
+pub async fn connect(address: SocketAddr, transport: TransportKind) -> Connection {
+    let mut connection = Connection::connect(address, transport, TIMEOUT).await.unwrap();
+    connection.handshake(credentials(), None).await.unwrap();
+    connection
+}
+
+pub async fn next_event(network: &mut NetworkClient) -> NetworkEvent {
+    for _ in 0..500 {
+        if let Some(event) = network.try_recv() {
+            return event;
+        }
+        tokio::time::delay_for(Duration::from_millis(10)).await;
+    }
+    panic!("No network events received");
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use nalgebra_glm as glm;
+
+use embercore_client_lib::network::{Interpolation, ServerClock};
+
+fn assert_near(actual: glm::Vec2, expected: [f32; 2], epsilon: f32) {
+    assert!(
+        (actual.x - expected[0]).abs() < epsilon && (actual.y - expected[1]).abs() < epsilon,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn entities_are_sampled_between_snapshots() {
+    let clock = ServerClock::new();
+    let mut interpolation = Interpolation::new(Duration::from_millis(100), Duration::from_millis(250));
+
+    // Rendered at `now - 0.1`, halfway between the snapshots
+    let now = clock.timestamp();
+    interpolation.push(now + 0.9, 1, glm::vec2(20.0, 10.0));
+    interpolation.push(now - 1.1, 1, glm::vec2(0.0, 10.0));
+    // Entities without newer snapshots stay at their latest position
+    interpolation.push(now - 1.1, 2, glm::vec2(5.0, 5.0));
+    interpolation.update(&clock);
+
+    // Tolerates the time passed since `now`
+    assert_near(interpolation.position(1).unwrap(), [10.0, 10.0], 0.1);
+    assert_near(interpolation.position(2).unwrap(), [5.0, 5.0], 1e-6);
+    assert_eq!(interpolation.positions().count(), 2);
+
+    interpolation.remove(2);
+    assert!(interpolation.position(2).is_none());
+}
+
+#[test]
+fn extrapolation_is_clamped() {
+    let clock = ServerClock::new();
+    let mut interpolation = Interpolation::new(Duration::from_millis(100), Duration::from_millis(250));
+
+    // The latest snapshot is a second behind the render time, the entity moves ten tiles per second
+    let now = clock.timestamp();
+    interpolation.push(now - 2.1, 1, glm::vec2(0.0, 0.0));
+    interpolation.push(now - 1.1, 1, glm::vec2(10.0, 0.0));
+    interpolation.update(&clock);
+
+    assert_near(interpolation.position(1).unwrap(), [12.5, 0.0], 1e-3);
+
+    // Catches up as soon as new snapshots arrive
+    interpolation.push(now + 0.9, 1, glm::vec2(30.0, 0.0));
+    interpolation.update(&clock);
+
+    assert_near(interpolation.position(1).unwrap(), [20.0, 0.0], 0.1);
+}
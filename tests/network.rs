@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
+use uuid::Uuid;
+
+use embercore_client_lib::network::*;
+
+mod common;
+
+use common::credentials;
+
+async fn spawn_server(protocol_version: u32, session: Uuid) -> std::net::SocketAddr {
+    common::spawn_server(move |mut transport| async move {
+        while let Some(Ok(message)) = transport.next().await {
+            let response = match message {
+                ClientMessage::Hello { .. } => ServerMessage::Welcome {
+                    protocol_version,
+                    session,
+                },
+                ClientMessage::Ping { id, client_time } => ServerMessage::Pong {
+                    id,
+                    client_time,
+                    server_time: Utc::now(),
+                },
+                _ => continue,
+            };
+            transport.send(response).await.unwrap();
+        }
+    })
+    .await
+}
+
+#[tokio::test]
+async fn connection_exchanges_messages_over_loopback() {
+    let session = Uuid::new_v4();
+    let address = spawn_server(PROTOCOL_VERSION, session).await;
+
+    let mut connection = Connection::connect(address, TransportKind::Tcp, common::TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(connection.handshake(credentials(), None).await.unwrap(), session);
+
+    let client_time = Utc::now();
+    connection
+        .send(ClientMessage::Ping { id: 42, client_time })
+        .await
+        .unwrap();
+    match connection.receive().await.unwrap() {
+        ServerMessage::Pong { id, server_time, .. } => {
+            assert_eq!(id, 42);
+
+            let clock = ServerClock::new();
+            clock.update(client_time, server_time, Utc::now());
+            assert!(clock.is_synchronized());
+            assert!(clock.offset() < chrono::Duration::seconds(1));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[tokio::test]
+async fn handshake_fails_on_protocol_version_mismatch() {
+    let address = spawn_server(PROTOCOL_VERSION + 1, Uuid::new_v4()).await;
+
+    let mut connection = Connection::connect(address, TransportKind::Tcp, common::TIMEOUT)
+        .await
+        .unwrap();
+    let error = connection.handshake(credentials(), None).await.unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<Error>(),
+        Some(Error::ProtocolVersionMismatch { .. })
+    ));
+}
+
+#[tokio::test]
+async fn client_reconnects_when_server_goes_silent() {
+    // Welcomes the client and then ignores everything while keeping the socket open
+    let address = common::spawn_server(|mut transport| async move {
+        common::welcome(&mut transport).await;
+        while let Some(Ok(_)) = transport.next().await {}
+    })
+    .await;
+
+    let mut config = common::config(address);
+    config.timeout_ms = 200;
+
+    let mut network = NetworkClient::spawn(&config);
+
+    assert!(matches!(
+        common::next_event(&mut network).await,
+        NetworkEvent::StateChanged(ConnectionState::Connecting)
+    ));
+    assert!(matches!(
+        common::next_event(&mut network).await,
+        NetworkEvent::StateChanged(ConnectionState::Connected { .. })
+    ));
+    assert!(matches!(
+        common::next_event(&mut network).await,
+        NetworkEvent::StateChanged(ConnectionState::Reconnecting { attempt: 1, .. })
+    ));
+}
+
+#[test]
+fn backoff_handles_zero_delays() {
+    let mut backoff = Backoff::new(Duration::from_secs(0), Duration::from_secs(0));
+    for _ in 0..4 {
+        assert!(backoff.next_delay() <= Duration::from_millis(1));
+    }
+    assert_eq!(backoff.attempt(), 4);
+}
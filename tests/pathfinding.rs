@@ -0,0 +1,64 @@
+use embercore_client_lib::collision::CollisionGrid;
+use embercore_client_lib::pathfinding::find_path;
+
+fn grid_from(rows: &[&str]) -> CollisionGrid {
+    let mut grid = CollisionGrid::new(rows[0].len() as u32, rows.len() as u32);
+    for (y, row) in rows.iter().enumerate() {
+        for (x, tile) in row.chars().enumerate() {
+            grid.set_blocked(x as u32, y as u32, tile == '#');
+        }
+    }
+    grid
+}
+
+#[test]
+fn open_grid_path_is_diagonal() {
+    let grid = CollisionGrid::new(8, 8);
+    assert_eq!(find_path(&grid, (1, 1), (4, 4)), Some(vec![(2, 2), (3, 3), (4, 4)]));
+    assert_eq!(find_path(&grid, (1, 1), (1, 1)), Some(vec![]));
+}
+
+#[test]
+fn path_goes_around_walls() {
+    let grid = grid_from(&[
+        "......", //
+        ".####.", //
+        "......", //
+    ]);
+    let path = find_path(&grid, (0, 2), (5, 2)).unwrap();
+    assert_eq!(path.len(), 5);
+    assert!(path.iter().all(|(x, y)| !grid.is_blocked(*x, *y)));
+
+    let path = find_path(&grid, (2, 0), (2, 2)).unwrap();
+    assert_eq!(path.len(), 6);
+    assert_eq!(path.last(), Some(&(2, 2)));
+}
+
+#[test]
+fn diagonals_do_not_cut_corners() {
+    let grid = grid_from(&[
+        "...", //
+        ".#.", //
+        "...", //
+    ]);
+    // Both diagonals past the blocked tile touch its corner
+    assert_eq!(find_path(&grid, (0, 1), (1, 0)), Some(vec![(0, 0), (1, 0)]));
+
+    let grid = grid_from(&[
+        ".#", //
+        "#.", //
+    ]);
+    assert_eq!(find_path(&grid, (0, 0), (1, 1)), None);
+}
+
+#[test]
+fn blocked_and_unreachable_goals_have_no_path() {
+    let grid = grid_from(&[
+        "..#..", //
+        "..#..", //
+        "..#..", //
+    ]);
+    assert_eq!(find_path(&grid, (0, 0), (2, 1)), None);
+    assert_eq!(find_path(&grid, (0, 0), (4, 2)), None);
+    assert_eq!(find_path(&grid, (0, 0), (-1, 0)), None);
+}
@@ -0,0 +1,69 @@
+use nalgebra_glm as glm;
+
+use embercore_client_lib::collision::CollisionGrid;
+use embercore_client_lib::network::{Prediction, PLAYER_HALF_SIZE, PLAYER_SPEED};
+
+fn assert_near(actual: &glm::Vec2, expected: [f32; 2]) {
+    assert!(
+        (actual.x - expected[0]).abs() < 1e-4 && (actual.y - expected[1]).abs() < 1e-4,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn reconciliation_replays_unacknowledged_inputs() {
+    let mut prediction = Prediction::new(glm::vec2(0.0, 0.0));
+
+    // Every input moves one tile
+    let dt = 1.0 / PLAYER_SPEED;
+    let sequences = [
+        prediction.apply_input(glm::vec2(1.0, 0.0), dt).sequence,
+        prediction.apply_input(glm::vec2(1.0, 0.0), dt).sequence,
+        prediction.apply_input(glm::vec2(0.0, 1.0), dt).sequence,
+    ];
+    assert_eq!(sequences, [0, 1, 2]);
+    assert_near(prediction.position(), [2.0, 1.0]);
+
+    // The server has processed the first input only and was pushed back by half a tile
+    prediction.reconcile(0, glm::vec2(0.5, 0.0));
+    assert_near(prediction.position(), [1.5, 1.0]);
+
+    // Repeated acks keep replaying the inputs the server hasn't processed
+    prediction.reconcile(0, glm::vec2(0.5, 0.0));
+    assert_near(prediction.position(), [1.5, 1.0]);
+
+    // Nothing is left to replay after every input is acknowledged
+    prediction.reconcile(2, glm::vec2(3.0, 3.0));
+    assert_near(prediction.position(), [3.0, 3.0]);
+
+    // New inputs continue the sequence
+    assert_eq!(prediction.apply_input(glm::vec2(-1.0, 0.0), dt).sequence, 3);
+    assert_near(prediction.position(), [2.0, 3.0]);
+}
+
+#[test]
+fn reconciliation_replays_inputs_against_walls() {
+    let mut grid = CollisionGrid::new(16, 16);
+    for y in 0..16 {
+        grid.set_blocked(10, y, true);
+    }
+
+    let mut prediction = Prediction::new(glm::vec2(8.0, 8.0));
+    prediction.set_collision(grid);
+
+    let dt = 1.0 / PLAYER_SPEED;
+    prediction.apply_input(glm::vec2(1.0, 0.0), dt);
+    prediction.apply_input(glm::vec2(1.0, 0.0), dt);
+    prediction.apply_input(glm::vec2(1.0, 0.0), dt);
+    assert_near(prediction.position(), [10.0 - PLAYER_HALF_SIZE, 8.0]);
+
+    // Replaying from an older position stops at the wall again
+    prediction.reconcile(0, glm::vec2(8.5, 8.0));
+    assert_near(prediction.position(), [10.0 - PLAYER_HALF_SIZE, 8.0]);
+
+    // One input short of the wall
+    prediction.reconcile(1, glm::vec2(7.0, 8.0));
+    assert_near(prediction.position(), [8.0, 8.0]);
+}
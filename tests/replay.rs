@@ -0,0 +1,97 @@
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
+
+use embercore_client_lib::network::*;
+
+mod common;
+
+fn messages() -> Vec<ServerMessage> {
+    let chat = |text: &str| ServerMessage::Chat {
+        from: "server".to_owned(),
+        text: text.to_owned(),
+        time: Utc::now(),
+    };
+
+    vec![
+        chat("first"),
+        ServerMessage::ChunkUnloaded { layer: 1, x: 2, y: 3 },
+        chat("second"),
+        chat("third"),
+    ]
+}
+
+/// Session and messages received until `count` messages arrive
+async fn receive_messages(network: &mut NetworkClient, count: usize) -> (uuid::Uuid, Vec<String>) {
+    let mut session = None;
+    let mut messages = Vec::new();
+    while messages.len() < count {
+        match common::next_event(network).await {
+            NetworkEvent::StateChanged(ConnectionState::Connected { session: connected }) => session = Some(connected),
+            NetworkEvent::StateChanged(_) => {}
+            NetworkEvent::Message(message) => messages.push(format!("{:?}", message)),
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+    (session.expect("Not connected"), messages)
+}
+
+/// Waits until the recorder has written `count` inbound messages
+async fn wait_for_recording(path: &Path, count: usize) {
+    for _ in 0..500 {
+        let mut replay = Replay::open(path).await.unwrap();
+        let mut inbound = 0;
+        while let Ok(Some(entry)) = replay.next().await {
+            if let ReplayEntry::Inbound { .. } = entry {
+                inbound += 1;
+            }
+        }
+        if inbound >= count {
+            return;
+        }
+        tokio::time::delay_for(Duration::from_millis(10)).await;
+    }
+    panic!("Replay file was not written");
+}
+
+#[tokio::test]
+async fn recorded_session_is_played_back_without_server() {
+    let sent = messages();
+    let expected = sent.iter().map(|message| format!("{:?}", message)).collect::<Vec<_>>();
+
+    let address = common::spawn_server(|mut transport| async move {
+        common::welcome(&mut transport).await;
+        for message in sent {
+            transport.send(message).await.unwrap();
+        }
+
+        while let Some(Ok(_)) = transport.next().await {}
+    })
+    .await;
+
+    let path = std::env::temp_dir().join(format!("embercore-session-{}.replay", std::process::id()));
+
+    let mut config = common::config(address);
+    config.record_file = Some(path.clone());
+    let mut network = NetworkClient::spawn(&config);
+
+    let (session, received) = receive_messages(&mut network, expected.len()).await;
+    assert_eq!(received, expected);
+    drop(network);
+
+    // The welcome and every message
+    wait_for_recording(&path, 1 + expected.len()).await;
+
+    // Nothing listens at the configured address during playback
+    let mut config = common::config("127.0.0.1:1".parse().unwrap());
+    config.replay_file = Some(path.clone());
+    let mut replay = NetworkClient::spawn(&config);
+
+    let (replayed_session, replayed) = receive_messages(&mut replay, expected.len()).await;
+    assert_eq!(replayed_session, session);
+    assert_eq!(replayed, expected);
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,142 @@
+use futures::{SinkExt, StreamExt};
+
+use embercore_client_lib::network::*;
+
+mod common;
+
+use common::ServerTransport;
+
+async fn spawn_server<F, Fut>(script: F) -> std::net::SocketAddr
+where
+    F: FnOnce(ServerTransport) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send,
+{
+    common::spawn_server(|mut transport| async move {
+        common::welcome(&mut transport).await;
+        script(transport).await;
+    })
+    .await
+}
+
+async fn connect(address: std::net::SocketAddr) -> Connection {
+    common::connect(address, TransportKind::Tcp).await
+}
+
+async fn next_client_message(transport: &mut ServerTransport) -> ClientMessage {
+    transport.next().await.unwrap().unwrap()
+}
+
+async fn next_delta(connection: &mut Connection) -> SnapshotDelta {
+    match connection.receive().await.unwrap() {
+        ServerMessage::Snapshot(delta) => delta,
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+fn entity(id: EntityId, x: f32, y: f32) -> EntityState {
+    EntityState { id, position: [x, y] }
+}
+
+fn first_state() -> Vec<EntityState> {
+    vec![entity(0, 1.0, 1.0), entity(1, 2.0, 2.0), entity(2, 3.0, 3.0)]
+}
+
+fn second_state() -> Vec<EntityState> {
+    vec![entity(0, 1.0, 1.0), entity(1, 2.5, 2.0), entity(3, 4.0, 4.0)]
+}
+
+#[tokio::test]
+async fn delta_snapshots_round_trip_over_loopback() {
+    let address = spawn_server(|mut transport| async move {
+        let mut snapshots = SnapshotSender::new();
+
+        transport
+            .send(ServerMessage::Snapshot(snapshots.encode(1.0, first_state())))
+            .await
+            .unwrap();
+
+        match next_client_message(&mut transport).await {
+            ClientMessage::SnapshotAck { sequence } => snapshots.acknowledge(sequence),
+            message => panic!("Unexpected message: {:?}", message),
+        }
+
+        transport
+            .send(ServerMessage::Snapshot(snapshots.encode(1.05, second_state())))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    let mut connection = connect(address).await;
+    let mut snapshots = SnapshotReceiver::new();
+
+    let delta = next_delta(&mut connection).await;
+    assert_eq!(delta.baseline, None);
+    assert_eq!(delta.changed.len(), 3);
+
+    let snapshot = snapshots.receive(delta).unwrap().unwrap().clone();
+    assert_eq!(snapshot, Snapshot::new(0, 1.0, first_state()));
+    connection
+        .send(ClientMessage::SnapshotAck {
+            sequence: snapshot.sequence,
+        })
+        .await
+        .unwrap();
+
+    let delta = next_delta(&mut connection).await;
+    assert_eq!(delta.baseline, Some(0));
+    assert_eq!(delta.changed, vec![entity(1, 2.5, 2.0), entity(3, 4.0, 4.0)]);
+    assert_eq!(delta.removed, vec![2]);
+
+    let snapshot = snapshots.receive(delta).unwrap().unwrap();
+    assert_eq!(snapshot, &Snapshot::new(1, 1.05, second_state()));
+}
+
+#[tokio::test]
+async fn missing_baseline_requests_full_snapshot() {
+    let address = spawn_server(|mut transport| async move {
+        let mut snapshots = SnapshotSender::new();
+
+        // The first snapshot is lost, but the server believes it was acknowledged
+        let lost = snapshots.encode(1.0, first_state());
+        snapshots.acknowledge(lost.sequence);
+
+        for time in &[1.05, 1.1] {
+            transport
+                .send(ServerMessage::Snapshot(snapshots.encode(*time, second_state())))
+                .await
+                .unwrap();
+        }
+
+        match next_client_message(&mut transport).await {
+            ClientMessage::RequestFullSnapshot => snapshots.reset(),
+            message => panic!("Unexpected message: {:?}", message),
+        }
+
+        transport
+            .send(ServerMessage::Snapshot(snapshots.encode(1.15, second_state())))
+            .await
+            .unwrap();
+    })
+    .await;
+
+    let mut connection = connect(address).await;
+    let mut snapshots = SnapshotReceiver::new();
+
+    let delta = next_delta(&mut connection).await;
+    assert!(matches!(snapshots.receive(delta), Err(Error::MissingBaseline(0))));
+    assert!(snapshots.request_full_snapshot());
+    connection.send(ClientMessage::RequestFullSnapshot).await.unwrap();
+
+    // Deltas that arrive before the full snapshot don't request it again
+    let delta = next_delta(&mut connection).await;
+    assert!(matches!(snapshots.receive(delta), Err(Error::MissingBaseline(0))));
+    assert!(!snapshots.request_full_snapshot());
+
+    let delta = next_delta(&mut connection).await;
+    assert_eq!(delta.baseline, None);
+
+    let snapshot = snapshots.receive(delta).unwrap().unwrap();
+    assert_eq!(snapshot, &Snapshot::new(3, 1.15, second_state()));
+    assert!(snapshots.request_full_snapshot());
+}
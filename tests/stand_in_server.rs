@@ -0,0 +1,82 @@
+use nalgebra_glm as glm;
+
+use embercore_client_lib::network::*;
+
+mod common;
+
+use common::next_event;
+
+#[tokio::test]
+async fn client_logs_in_and_receives_map() {
+    let config = common::config(common::spawn_stand_in_server().await);
+
+    let mut network = NetworkClient::spawn(&config);
+
+    assert!(matches!(
+        next_event(&mut network).await,
+        NetworkEvent::StateChanged(ConnectionState::Connecting)
+    ));
+    assert!(matches!(
+        next_event(&mut network).await,
+        NetworkEvent::StateChanged(ConnectionState::Connected { .. })
+    ));
+
+    // The bundled map is 64x64 tiles with two tile layers and an object layer
+    match next_event(&mut network).await {
+        NetworkEvent::Message(ServerMessage::MapInfo {
+            width,
+            height,
+            objects,
+            y_sorted_layers,
+            player_position,
+            ..
+        }) => {
+            assert_eq!([width, height], [64, 64]);
+            assert_eq!(y_sorted_layers, [1]);
+
+            let kinds = objects.iter().map(|object| object.kind.as_str()).collect::<Vec<_>>();
+            assert_eq!(kinds, ["npc", "trigger"]);
+
+            // New players start at the spawn point, which is kept on the server
+            assert_eq!(player_position, [8.0, 8.0]);
+
+            let trigger = &objects[1];
+            assert_eq!(trigger.position, [21.5, 11.0]);
+            assert_eq!(trigger.size, [3.0, 2.0]);
+            assert_eq!(
+                trigger.properties.get("message"),
+                Some(&PropertyValue::String("You hear birds in the trees".to_owned()))
+            );
+        }
+        event => panic!("Unexpected event: {:?}", event),
+    }
+    for _ in 0..2 * 4 * 4 {
+        match next_event(&mut network).await {
+            NetworkEvent::Message(ServerMessage::ChunkLoaded(chunk)) => {
+                assert_eq!(chunk.tiles.len(), 16 * 16);
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+}
+
+#[tokio::test]
+async fn server_echoes_movement() {
+    let mut connection = common::connect(common::spawn_stand_in_server().await, TransportKind::Tcp).await;
+
+    let mut prediction = Prediction::new(glm::vec2(8.0, 8.0));
+    let command = prediction.apply_input(glm::vec2(1.0, 0.0), 0.5);
+    connection.send(ClientMessage::Input(command)).await.unwrap();
+
+    loop {
+        match connection.receive().await.unwrap() {
+            ServerMessage::PlayerState { last_input, position } => {
+                assert_eq!(last_input, command.sequence);
+                assert_eq!(position, [prediction.position().x, prediction.position().y]);
+                break;
+            }
+            ServerMessage::MapInfo { .. } | ServerMessage::ChunkLoaded(_) | ServerMessage::Snapshot(_) => continue,
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::time::{Duration, Instant};
+
+use embercore_client_lib::network::*;
+
+mod common;
+
+fn deliver(endpoint: &mut Endpoint, packets: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    packets
+        .iter()
+        .flat_map(|packet| endpoint.receive(packet).unwrap())
+        .collect()
+}
+
+#[test]
+fn reliable_messages_survive_packet_loss() {
+    let mut client = Endpoint::new();
+    let mut server = Endpoint::new();
+    let now = Instant::now();
+
+    client.push(Channel::ReliableOrdered, vec![1]);
+    let _lost = client.flush(now).unwrap();
+
+    client.push(Channel::ReliableOrdered, vec![2]);
+    let packets = client.flush(now).unwrap();
+    assert!(deliver(&mut server, &packets).is_empty());
+
+    // Acknowledges the second packet only
+    let acks = server.flush(now).unwrap();
+    assert_eq!(acks.len(), 1);
+    assert!(deliver(&mut client, &acks).is_empty());
+
+    assert!(client.flush(now).unwrap().is_empty());
+
+    let packets = client.flush(now + RESEND_DELAY).unwrap();
+    assert_eq!(deliver(&mut server, &packets), vec![vec![1], vec![2]]);
+
+    // Duplicates are not delivered twice
+    assert!(deliver(&mut server, &packets).is_empty());
+}
+
+#[test]
+fn unreliable_messages_are_sequenced() {
+    let mut client = Endpoint::new();
+    let mut server = Endpoint::new();
+    let now = Instant::now();
+
+    client.push(Channel::UnreliableSequenced, vec![1]);
+    let first = client.flush(now).unwrap();
+    client.push(Channel::UnreliableSequenced, vec![2]);
+    let second = client.flush(now).unwrap();
+
+    assert_eq!(deliver(&mut server, &second), vec![vec![2]]);
+    assert!(deliver(&mut server, &first).is_empty());
+
+    // Unreliable messages are never resent
+    assert!(client.flush(now + RESEND_DELAY).unwrap().is_empty());
+}
+
+#[test]
+fn unsequenced_messages_are_delivered_late() {
+    let mut client = Endpoint::new();
+    let mut server = Endpoint::new();
+    let now = Instant::now();
+
+    client.push(Channel::Unreliable, vec![1]);
+    let first = client.flush(now).unwrap();
+    client.push(Channel::UnreliableSequenced, vec![2]);
+    let second = client.flush(now).unwrap();
+
+    assert_eq!(deliver(&mut server, &second), vec![vec![2]]);
+    assert_eq!(deliver(&mut server, &first), vec![vec![1]]);
+    assert!(deliver(&mut server, &first).is_empty());
+}
+
+#[test]
+fn lost_and_late_packets_are_counted() {
+    let mut client = Endpoint::new();
+    let mut server = Endpoint::new();
+    let now = Instant::now();
+
+    let mut packets = (0..40u8)
+        .map(|i| {
+            client.push(Channel::UnreliableSequenced, vec![i]);
+            client.flush(now).unwrap().remove(0)
+        })
+        .collect::<Vec<_>>();
+
+    packets.remove(3);
+    packets.swap(3, 4);
+    deliver(&mut server, &packets);
+
+    assert_eq!(server.dropped_packets(), 1);
+    assert_eq!(server.late_packets(), 1);
+}
+
+#[tokio::test]
+async fn client_logs_in_over_udp() {
+    let mut connection = common::connect(common::spawn_stand_in_server().await, TransportKind::Udp).await;
+
+    // The bundled map is 64x64 tiles with two tile layers
+    let mut chunks = 0;
+    while chunks < 2 * 4 * 4 {
+        match tokio::time::timeout(Duration::from_secs(5), connection.receive())
+            .await
+            .unwrap()
+            .unwrap()
+        {
+            ServerMessage::ChunkLoaded(_) => chunks += 1,
+            ServerMessage::MapInfo { .. } | ServerMessage::Snapshot(_) => continue,
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+
+    let client_time = chrono::Utc::now();
+    connection
+        .send(ClientMessage::Ping { id: 1, client_time })
+        .await
+        .unwrap();
+    loop {
+        match connection.receive().await.unwrap() {
+            ServerMessage::Pong { id, .. } => {
+                assert_eq!(id, 1);
+                break;
+            }
+            ServerMessage::Snapshot(_) => continue,
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+}
+
+#[tokio::test]
+async fn silent_udp_server_times_out() {
+    // Receives everything and never answers
+    let silent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let timeout = Duration::from_millis(200);
+
+    let mut connection = Connection::connect(silent.local_addr().unwrap(), TransportKind::Udp, timeout)
+        .await
+        .unwrap();
+    connection
+        .send(ClientMessage::Ping {
+            id: 1,
+            client_time: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+    let error = tokio::time::timeout(Duration::from_secs(5), connection.receive())
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert!(matches!(error.downcast_ref::<Error>(), Some(Error::Timeout(_))));
+}